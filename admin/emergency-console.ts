@@ -22,24 +22,32 @@ class EmergencyConsole {
         this.program = new Program(GentStaking.IDL, new PublicKey(process.env.PROGRAM_ID!), provider);
     }
 
-    async emergencyUnstake(userPubkey: PublicKey) {
+    async emergencyUnstake(userPubkey: PublicKey, positionIndex: number, amount: anchor.BN) {
+        const stakingPool = await this.getStakingPoolAddress();
         const [stakerInfo] = await PublicKey.findProgramAddress(
-            [Buffer.from('staker'), userPubkey.toBuffer()],
+            [Buffer.from('staker'), stakingPool.toBuffer(), userPubkey.toBuffer()],
+            this.program.programId
+        );
+        const [stakePosition] = await PublicKey.findProgramAddress(
+            [Buffer.from('position'), stakerInfo.toBuffer(), new anchor.BN(positionIndex).toArrayLike(Buffer, 'le', 8)],
             this.program.programId
         );
 
-        console.log(`Initiating emergency unstake for user: ${userPubkey.toString()}`);
+        console.log(`Initiating emergency unstake for user: ${userPubkey.toString()}, position: ${positionIndex}, amount: ${amount.toString()}`);
         
         try {
+            const rewardStreams = await this.getRewardStreamAccounts(stakingPool);
             await this.program.methods
-                .emergencyUnstake()
+                .emergencyUnstake(amount)
                 .accounts({
-                    stakingPool: await this.getStakingPoolAddress(),
+                    stakingPool,
                     stakerInfo,
+                    stakePosition,
                     user: userPubkey,
                     emergencyAdmin: this.adminKeypair.publicKey,
                     // ... other required accounts
                 })
+                .remainingAccounts(rewardStreams)
                 .signers([this.adminKeypair])
                 .rpc();
             
@@ -49,23 +57,83 @@ class EmergencyConsole {
         }
     }
 
-    async pausePool() {
+    async pausePool(reason: string = 'securityIncident') {
         try {
-            // Implementation for pausing the staking pool
+            await this.program.methods
+                .pausePool({ [reason]: {} })
+                .accounts({
+                    stakingPool: await this.getStakingPoolAddress(),
+                    admin: this.adminKeypair.publicKey,
+                })
+                .signers([this.adminKeypair])
+                .rpc();
+
             console.log('Staking pool paused successfully');
         } catch (error) {
             console.error('Failed to pause pool:', error);
         }
     }
 
+    async resumePool() {
+        try {
+            await this.program.methods
+                .resumePool()
+                .accounts({
+                    stakingPool: await this.getStakingPoolAddress(),
+                    admin: this.adminKeypair.publicKey,
+                })
+                .signers([this.adminKeypair])
+                .rpc();
+
+            console.log('Staking pool resumed successfully');
+        } catch (error) {
+            console.error('Failed to resume pool:', error);
+        }
+    }
+
+    async setEmergencyMode(enabled: boolean) {
+        try {
+            await this.program.methods
+                .setEmergencyMode(enabled)
+                .accounts({
+                    stakingPool: await this.getStakingPoolAddress(),
+                    emergencyAdmin: this.adminKeypair.publicKey,
+                })
+                .signers([this.adminKeypair])
+                .rpc();
+
+            console.log(`Emergency mode ${enabled ? 'enabled' : 'disabled'} successfully`);
+        } catch (error) {
+            console.error('Failed to update emergency mode:', error);
+        }
+    }
+
     async getStakingPoolAddress(): Promise<PublicKey> {
         const [stakingPool] = await PublicKey.findProgramAddress(
-            [Buffer.from('staking_pool')],
+            [
+                Buffer.from('staking_pool'),
+                new PublicKey(process.env.STAKE_MINT!).toBuffer(),
+                new anchor.BN(process.env.POOL_ID || '0').toArrayLike(Buffer, 'le', 8),
+            ],
             this.program.programId
         );
         return stakingPool;
     }
 
+    // Weight-changing instructions take every reward stream of the pool, in order
+    async getRewardStreamAccounts(stakingPool: PublicKey) {
+        const poolData = await this.program.account.stakingPool.fetch(stakingPool);
+        const streams = [];
+        for (let index = 0; index < poolData.rewardStreamCount; index++) {
+            const [rewardVault] = await PublicKey.findProgramAddress(
+                [Buffer.from('reward_vault'), stakingPool.toBuffer(), Buffer.from([index])],
+                this.program.programId
+            );
+            streams.push({ pubkey: rewardVault, isWritable: true, isSigner: false });
+        }
+        return streams;
+    }
+
     async showPoolStatus() {
         const poolAddress = await this.getStakingPoolAddress();
         const poolData = await this.program.account.stakingPool.fetch(poolAddress);
@@ -75,7 +143,15 @@ class EmergencyConsole {
         console.log(`Total Staked: ${poolData.totalStaked.toString()}`);
         console.log(`Total Rewards Distributed: ${poolData.totalRewardsDistributed.toString()}`);
         console.log(`Paused: ${poolData.paused}`);
-        console.log(`Stake Count: ${poolData.stakeCount.toString()}`);
+        console.log(`Emergency Mode: ${poolData.emergencyMode}`);
+        if (poolData.paused) {
+            console.log(`Pause Reason: ${poolData.pauseReason}`);
+            console.log(`Paused At: ${new Date(poolData.pausedAt.toNumber() * 1000).toISOString()}`);
+        }
+        console.log(`Active Stakers: ${poolData.activeStakers.toString()}`);
+        console.log(`Total Deposits: ${poolData.totalDeposits.toString()}`);
+        console.log(`Total Withdrawals: ${poolData.totalWithdrawals.toString()}`);
+        console.log(`Reward Streams: ${poolData.rewardStreamCount}`);
     }
 
     async startConsole() {
@@ -91,8 +167,11 @@ class EmergencyConsole {
             rl.question(`
 1. Show Pool Status
 2. Pause Pool
-3. Emergency Unstake
-4. Exit
+3. Resume Pool
+4. Emergency Unstake
+5. Enable Emergency Mode
+6. Disable Emergency Mode
+7. Exit
 
 Select option: `, async (answer) => {
                 switch(answer) {
@@ -105,12 +184,28 @@ Select option: `, async (answer) => {
                         prompt();
                         break;
                     case '3':
+                        await this.resumePool();
+                        prompt();
+                        break;
+                    case '4':
                         rl.question('Enter user public key: ', async (pubkey) => {
-                            await this.emergencyUnstake(new PublicKey(pubkey));
-                            prompt();
+                            rl.question('Enter position index: ', async (index) => {
+                                rl.question('Enter amount: ', async (amount) => {
+                                    await this.emergencyUnstake(new PublicKey(pubkey), parseInt(index, 10), new anchor.BN(amount));
+                                    prompt();
+                                });
+                            });
                         });
                         break;
-                    case '4':
+                    case '5':
+                        await this.setEmergencyMode(true);
+                        prompt();
+                        break;
+                    case '6':
+                        await this.setEmergencyMode(false);
+                        prompt();
+                        break;
+                    case '7':
                         rl.close();
                         process.exit(0);
                         break;
@@ -23,15 +23,22 @@ async function deploy() {
     const program = new Program(GentStaking.IDL, new PublicKey(process.env.PROGRAM_ID!), provider);
 
     try {
+        const stakeMint = new PublicKey(process.env.STAKE_MINT!);
+        const poolId = new anchor.BN(process.env.POOL_ID || '0');
+
         // Create staking pool
         const [stakingPool, stakingPoolBump] = await PublicKey.findProgramAddress(
-            [Buffer.from('staking_pool')],
+            [Buffer.from('staking_pool'), stakeMint.toBuffer(), poolId.toArrayLike(Buffer, 'le', 8)],
+            program.programId
+        );
+        const [stakeVault] = await PublicKey.findProgramAddress(
+            [Buffer.from('stake_vault'), stakingPool.toBuffer()],
             program.programId
         );
 
-        // Create reward vault
+        // First reward stream of the pool
         const [rewardVault, rewardVaultBump] = await PublicKey.findProgramAddress(
-            [Buffer.from('reward_vault')],
+            [Buffer.from('reward_vault'), stakingPool.toBuffer(), Buffer.from([0])],
             program.programId
         );
 
@@ -40,24 +47,35 @@ async function deploy() {
             earlyAdopterPeriod: new anchor.BN(7 * 24 * 60 * 60), // 7 days
             minStakeDuration: new anchor.BN(30 * 24 * 60 * 60),  // 30 days
             maxStakeDuration: new anchor.BN(365 * 24 * 60 * 60), // 1 year
-            rewardsMultiplier: new anchor.BN(100),               // 1%
+            rewardsMultiplier: new anchor.BN(10000),             // 1x
             treasuryFee: new anchor.BN(500),                     // 5%
+            adminTimelockDelay: new anchor.BN(2 * 24 * 60 * 60), // 2 days
+            unbondingPeriod: new anchor.BN(0),                   // Instant unstake
+            earlyUnstakePenalty: new anchor.BN(2000),            // 20% at lock start
+            penaltyDestination: { treasury: {} },
+            keeperFee: new anchor.BN(50),                        // 0.5% crank bounty
+            minCompoundInterval: new anchor.BN(24 * 60 * 60),    // Daily auto-compound
         };
 
         console.log('Initializing staking pool...');
         await program.methods
-            .initializePool(config)
+            .initializePool(poolId, config)
             .accounts({
                 stakingPool,
+                stakeMint,
+                stakeVault,
                 authority: wallet.publicKey,
                 treasury: new PublicKey(process.env.TREASURY_ADDRESS!),
                 emergencyAdmin: new PublicKey(process.env.EMERGENCY_ADMIN!),
+                tokenProgram: TOKEN_PROGRAM_ID,
                 systemProgram: anchor.web3.SystemProgram.programId,
+                rent: anchor.web3.SYSVAR_RENT_PUBKEY,
             })
             .rpc();
 
         console.log('Deployment completed successfully!');
         console.log('Staking Pool Address:', stakingPool.toString());
+        console.log('Stake Vault Address:', stakeVault.toString());
         console.log('Reward Vault Address:', rewardVault.toString());
 
     } catch (error) {
@@ -10,6 +10,7 @@ class StakingMonitor {
     private program: Program<GentStaking>;
     private connection: Connection;
     private stakingPool: PublicKey;
+    private rewardVault: PublicKey;
 
     constructor() {
         this.connection = new Connection(process.env.SOLANA_RPC_URL || 'http://localhost:8899');
@@ -23,7 +24,15 @@ class StakingMonitor {
 
     async initialize() {
         [this.stakingPool] = await PublicKey.findProgramAddress(
-            [Buffer.from('staking_pool')],
+            [
+                Buffer.from('staking_pool'),
+                new PublicKey(process.env.STAKE_MINT!).toBuffer(),
+                new anchor.BN(process.env.POOL_ID || '0').toArrayLike(Buffer, 'le', 8),
+            ],
+            this.program.programId
+        );
+        [this.rewardVault] = await PublicKey.findProgramAddress(
+            [Buffer.from('reward_vault'), this.stakingPool.toBuffer(), Buffer.from([0])],
             this.program.programId
         );
     }
@@ -31,11 +40,28 @@ class StakingMonitor {
     async monitorPoolMetrics() {
         try {
             const poolData = await this.program.account.stakingPool.fetch(this.stakingPool);
+            const rewardVaultData = await this.program.account.rewardVault.fetch(this.rewardVault);
+            const stats = await this.program.methods
+                .getPoolStats()
+                .accounts({
+                    stakingPool: this.stakingPool,
+                    rewardVault: this.rewardVault,
+                    rewardVaultToken: rewardVaultData.tokenAccount,
+                })
+                .view();
             
             const metrics = {
-                totalStaked: poolData.totalStaked.toString(),
-                totalRewardsDistributed: poolData.totalRewardsDistributed.toString(),
-                stakeCount: poolData.stakeCount.toString(),
+                totalStaked: stats.totalStaked.toString(),
+                totalUnbonding: stats.totalUnbonding.toString(),
+                totalRewardsDistributed: stats.totalRewardsDistributed.toString(),
+                totalTreasuryFees: stats.totalTreasuryFees.toString(),
+                totalPenalties: stats.totalPenalties.toString(),
+                rewardStreams: stats.rewardStreamCount,
+                rewardVaultBalance: stats.rewardVaultBalance.toString(),
+                unclaimedRewards: stats.unclaimedRewards.toString(),
+                activeStakers: stats.activeStakers.toString(),
+                totalDeposits: stats.totalDeposits.toString(),
+                totalWithdrawals: stats.totalWithdrawals.toString(),
                 isPaused: poolData.paused,
                 timestamp: new Date().toISOString()
             };
@@ -65,7 +91,7 @@ class StakingMonitor {
                     
                     console.log('Staking pool updated:', {
                         totalStaked: account.totalStaked.toString(),
-                        stakeCount: account.stakeCount.toString(),
+                        activeStakers: account.activeStakers.toString(),
                         timestamp: new Date().toISOString()
                     });
                 } catch (error) {
@@ -1,57 +1,859 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Token, TokenAccount};
+use anchor_spl::token::{Mint, Token, TokenAccount};
 use crate::state::*;
+use crate::errors::*;
 
 #[derive(Accounts)]
+#[instruction(pool_id: u64)]
 pub struct InitializePool<'info> {
     #[account(
         init,
         payer = authority,
-        space = 8 + 32 + 32 + 32 + 8 + 8 + 8 + 8 + 1 + 8 + 8 + 8 + 8 + 8 + 1,
-        seeds = [b"staking_pool"],
+        space = 8 + 32 + 32 + 32 + 32 + 8 + 32 + 33 + 33 + 33 + 33 + 8 + 8 + 8 + 8 + 8 + 8 + 1 + 1 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 1 + 8 + 8 + 1 + 1 + 8 + 8 + 8 + 8 + 1,
+        seeds = [
+            b"staking_pool",
+            stake_mint.key().as_ref(),
+            &pool_id.to_le_bytes(),
+        ],
         bump
     )]
     pub staking_pool: Account<'info, StakingPool>,
     
+    pub stake_mint: Account<'info, Mint>,
+    
+    #[account(
+        init,
+        payer = authority,
+        token::mint = stake_mint,
+        token::authority = staking_pool,
+        seeds = [b"stake_vault", staking_pool.key().as_ref()],
+        bump
+    )]
+    pub stake_vault: Account<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    /// CHECK: Safe because this read-only account only stores funds
+    pub treasury: AccountInfo<'info>,
+    
+    /// CHECK: Safe because this read-only account is only used for emergency functions
+    pub emergency_admin: AccountInfo<'info>,
+    
+    pub token_program: Program<'info, Token>,
+    
+    pub system_program: Program<'info, System>,
+    
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeRewardVault<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"staking_pool",
+            staking_pool.stake_mint.as_ref(),
+            &staking_pool.pool_id.to_le_bytes(),
+        ],
+        bump = staking_pool.bump,
+        has_one = authority @ StakingError::UnauthorizedAccess,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 32 + 1 + 32 + 32 + 32 + 8 + 8 + 8 + 8 + 16 + 8 + 1,
+        seeds = [
+            b"reward_vault",
+            staking_pool.key().as_ref(),
+            &[staking_pool.reward_stream_count],
+        ],
+        bump
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    
+    pub reward_mint: Account<'info, Mint>,
+    
+    #[account(
+        init,
+        payer = authority,
+        token::mint = reward_mint,
+        token::authority = reward_vault,
+        seeds = [b"reward_vault_token", reward_vault.key().as_ref()],
+        bump
+    )]
+    pub reward_vault_token: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = funder_token_account.owner == authority.key() @ StakingError::UnauthorizedAccess,
+        constraint = funder_token_account.mint == reward_mint.key() @ StakingError::MintMismatch,
+    )]
+    pub funder_token_account: Account<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+    
+    pub system_program: Program<'info, System>,
+    
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct FundRewardVault<'info> {
+    #[account(
+        seeds = [
+            b"staking_pool",
+            staking_pool.stake_mint.as_ref(),
+            &staking_pool.pool_id.to_le_bytes(),
+        ],
+        bump = staking_pool.bump,
+        has_one = authority @ StakingError::UnauthorizedAccess,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        seeds = [
+            b"reward_vault",
+            staking_pool.key().as_ref(),
+            &[reward_vault.index],
+        ],
+        bump = reward_vault.bump,
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    
+    #[account(
+        mut,
+        constraint = reward_vault_token.key() == reward_vault.token_account @ StakingError::InvalidTokenAccount
+    )]
+    pub reward_vault_token: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = funder_token_account.owner == authority.key() @ StakingError::UnauthorizedAccess,
+        constraint = funder_token_account.mint == reward_vault.reward_mint @ StakingError::MintMismatch,
+    )]
+    pub funder_token_account: Account<'info, TokenAccount>,
+    
+    pub authority: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SetRewardRate<'info> {
+    #[account(
+        seeds = [
+            b"staking_pool",
+            staking_pool.stake_mint.as_ref(),
+            &staking_pool.pool_id.to_le_bytes(),
+        ],
+        bump = staking_pool.bump,
+        has_one = authority @ StakingError::UnauthorizedAccess,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"reward_vault",
+            staking_pool.key().as_ref(),
+            &[reward_vault.index],
+        ],
+        bump = reward_vault.bump,
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    
+    #[account(
+        constraint = reward_vault_token.key() == reward_vault.token_account @ StakingError::InvalidTokenAccount
+    )]
+    pub reward_vault_token: Account<'info, TokenAccount>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct NotifyRewardAmount<'info> {
+    #[account(
+        seeds = [
+            b"staking_pool",
+            staking_pool.stake_mint.as_ref(),
+            &staking_pool.pool_id.to_le_bytes(),
+        ],
+        bump = staking_pool.bump,
+        has_one = authority @ StakingError::UnauthorizedAccess,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"reward_vault",
+            staking_pool.key().as_ref(),
+            &[reward_vault.index],
+        ],
+        bump = reward_vault.bump,
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    
+    #[account(
+        mut,
+        constraint = reward_vault_token.key() == reward_vault.token_account @ StakingError::InvalidTokenAccount
+    )]
+    pub reward_vault_token: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = funder_token_account.owner == authority.key() @ StakingError::UnauthorizedAccess,
+        constraint = funder_token_account.mint == reward_vault.reward_mint @ StakingError::MintMismatch,
+    )]
+    pub funder_token_account: Account<'info, TokenAccount>,
+    
+    pub authority: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct RecoverLeftoverRewards<'info> {
+    #[account(
+        seeds = [
+            b"staking_pool",
+            staking_pool.stake_mint.as_ref(),
+            &staking_pool.pool_id.to_le_bytes(),
+        ],
+        bump = staking_pool.bump,
+        has_one = authority @ StakingError::UnauthorizedAccess,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"reward_vault",
+            staking_pool.key().as_ref(),
+            &[reward_vault.index],
+        ],
+        bump = reward_vault.bump,
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    
+    #[account(
+        mut,
+        constraint = reward_vault_token.key() == reward_vault.token_account @ StakingError::InvalidTokenAccount
+    )]
+    pub reward_vault_token: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = destination_token_account.mint == reward_vault.reward_mint @ StakingError::MintMismatch,
+    )]
+    pub destination_token_account: Account<'info, TokenAccount>,
+    
+    pub authority: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct UpdatePoolConfig<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"staking_pool",
+            staking_pool.stake_mint.as_ref(),
+            &staking_pool.pool_id.to_le_bytes(),
+        ],
+        bump = staking_pool.bump,
+        has_one = authority @ StakingError::UnauthorizedAccess,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct QueueAdminAction<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"staking_pool",
+            staking_pool.stake_mint.as_ref(),
+            &staking_pool.pool_id.to_le_bytes(),
+        ],
+        bump = staking_pool.bump,
+        has_one = authority @ StakingError::UnauthorizedAccess,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 32 + 32 + 8 + 17 + 8 + 8 + 1 + 1 + 1,
+        seeds = [
+            b"admin_proposal",
+            staking_pool.key().as_ref(),
+            &staking_pool.proposal_count.to_le_bytes(),
+        ],
+        bump
+    )]
+    pub admin_proposal: Account<'info, AdminProposal>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelAdminAction<'info> {
+    #[account(
+        seeds = [
+            b"staking_pool",
+            staking_pool.stake_mint.as_ref(),
+            &staking_pool.pool_id.to_le_bytes(),
+        ],
+        bump = staking_pool.bump,
+        has_one = authority @ StakingError::UnauthorizedAccess,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"admin_proposal",
+            staking_pool.key().as_ref(),
+            &admin_proposal.id.to_le_bytes(),
+        ],
+        bump = admin_proposal.bump,
+    )]
+    pub admin_proposal: Account<'info, AdminProposal>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteAdminAction<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"staking_pool",
+            staking_pool.stake_mint.as_ref(),
+            &staking_pool.pool_id.to_le_bytes(),
+        ],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"admin_proposal",
+            staking_pool.key().as_ref(),
+            &admin_proposal.id.to_le_bytes(),
+        ],
+        bump = admin_proposal.bump,
+    )]
+    pub admin_proposal: Account<'info, AdminProposal>,
+    
+    pub executor: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeAdminSet<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"staking_pool",
+            staking_pool.stake_mint.as_ref(),
+            &staking_pool.pool_id.to_le_bytes(),
+        ],
+        bump = staking_pool.bump,
+        has_one = authority @ StakingError::UnauthorizedAccess,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 32 + 4 + 32 * MAX_ADMIN_SIGNERS + 1 + 1,
+        seeds = [b"admin_set", staking_pool.key().as_ref()],
+        bump
+    )]
+    pub admin_set: Account<'info, AdminSet>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateAdminSet<'info> {
+    #[account(
+        seeds = [
+            b"staking_pool",
+            staking_pool.stake_mint.as_ref(),
+            &staking_pool.pool_id.to_le_bytes(),
+        ],
+        bump = staking_pool.bump,
+        has_one = authority @ StakingError::UnauthorizedAccess,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        mut,
+        seeds = [b"admin_set", staking_pool.key().as_ref()],
+        bump = admin_set.bump,
+    )]
+    pub admin_set: Account<'info, AdminSet>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeAdminTransfer<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"staking_pool",
+            staking_pool.stake_mint.as_ref(),
+            &staking_pool.pool_id.to_le_bytes(),
+        ],
+        bump = staking_pool.bump,
+        has_one = authority @ StakingError::UnauthorizedAccess,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAdminTransfer<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"staking_pool",
+            staking_pool.stake_mint.as_ref(),
+            &staking_pool.pool_id.to_le_bytes(),
+        ],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    pub new_admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetPoolPaused<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"staking_pool",
+            staking_pool.stake_mint.as_ref(),
+            &staking_pool.pool_id.to_le_bytes(),
+        ],
+        bump = staking_pool.bump,
+        constraint = admin.key() == staking_pool.authority
+            || admin.key() == staking_pool.emergency_admin @ StakingError::UnauthorizedAccess,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CreateStakeAccount<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"staking_pool",
+            staking_pool.stake_mint.as_ref(),
+            &staking_pool.pool_id.to_le_bytes(),
+        ],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + 32 + 32 + 33 + 8 + 8 + 8 + 8 + 8 * MAX_REWARD_STREAMS + 1 + 1 + 8 * MAX_REWARD_STREAMS + 8 + 16 * MAX_REWARD_STREAMS + 8 + 8 + 8 + 8 + 8 + 1 + 8 + 8 + 8 + 1,
+        seeds = [b"staker", staking_pool.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub staker_info: Account<'info, StakerInfo>,
+    
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseStakeAccount<'info> {
+    #[account(
+        seeds = [
+            b"staking_pool",
+            staking_pool.stake_mint.as_ref(),
+            &staking_pool.pool_id.to_le_bytes(),
+        ],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"staker", staking_pool.key().as_ref(), owner.key().as_ref()],
+        bump = staker_info.bump,
+        constraint = staker_info.owner == owner.key() @ StakingError::UnauthorizedAccess,
+    )]
+    pub staker_info: Account<'info, StakerInfo>,
+    
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Stake<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"staking_pool",
+            staking_pool.stake_mint.as_ref(),
+            &staking_pool.pool_id.to_le_bytes(),
+        ],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        mut,
+        seeds = [b"staker", staking_pool.key().as_ref(), user.key().as_ref()],
+        bump = staker_info.bump,
+        constraint = staker_info.owner == user.key() @ StakingError::UnauthorizedAccess,
+    )]
+    pub staker_info: Account<'info, StakerInfo>,
+    
+    #[account(
+        init,
+        payer = user,
+        space = 8 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 1 + 8 + 1,
+        seeds = [
+            b"position",
+            staker_info.key().as_ref(),
+            &staker_info.position_count.to_le_bytes(),
+        ],
+        bump
+    )]
+    pub stake_position: Account<'info, StakePosition>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = user_token_account.owner == user.key() @ StakingError::UnauthorizedAccess,
+        constraint = user_token_account.mint == staking_pool.stake_mint @ StakingError::MintMismatch,
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = stake_token_account.key() == staking_pool.stake_vault @ StakingError::InvalidTokenAccount
+    )]
+    pub stake_token_account: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Unstake<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"staking_pool",
+            staking_pool.stake_mint.as_ref(),
+            &staking_pool.pool_id.to_le_bytes(),
+        ],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        mut,
+        seeds = [b"staker", staking_pool.key().as_ref(), user.key().as_ref()],
+        bump = staker_info.bump,
+        constraint = staker_info.owner == user.key() @ StakingError::UnauthorizedAccess,
+    )]
+    pub staker_info: Account<'info, StakerInfo>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"position",
+            staker_info.key().as_ref(),
+            &stake_position.index.to_le_bytes(),
+        ],
+        bump = stake_position.bump,
+    )]
+    pub stake_position: Account<'info, StakePosition>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = user_token_account.owner == user.key() @ StakingError::UnauthorizedAccess,
+        constraint = user_token_account.mint == staking_pool.stake_mint @ StakingError::MintMismatch,
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = stake_token_account.key() == staking_pool.stake_vault @ StakingError::InvalidTokenAccount
+    )]
+    pub stake_token_account: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct EarlyUnstake<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"staking_pool",
+            staking_pool.stake_mint.as_ref(),
+            &staking_pool.pool_id.to_le_bytes(),
+        ],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        mut,
+        seeds = [b"staker", staking_pool.key().as_ref(), user.key().as_ref()],
+        bump = staker_info.bump,
+        constraint = staker_info.owner == user.key() @ StakingError::UnauthorizedAccess,
+    )]
+    pub staker_info: Account<'info, StakerInfo>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"position",
+            staker_info.key().as_ref(),
+            &stake_position.index.to_le_bytes(),
+        ],
+        bump = stake_position.bump,
+    )]
+    pub stake_position: Account<'info, StakePosition>,
+    
     #[account(mut)]
-    pub authority: Signer<'info>,
+    pub user: Signer<'info>,
     
-    /// CHECK: Safe because this read-only account only stores funds
-    pub treasury: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = user_token_account.owner == user.key() @ StakingError::UnauthorizedAccess,
+        constraint = user_token_account.mint == staking_pool.stake_mint @ StakingError::MintMismatch,
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
     
-    /// CHECK: Safe because this read-only account is only used for emergency functions
-    pub emergency_admin: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = stake_token_account.key() == staking_pool.stake_vault @ StakingError::InvalidTokenAccount
+    )]
+    pub stake_token_account: Account<'info, TokenAccount>,
     
-    pub system_program: Program<'info, System>,
+    #[account(mut)]
+    pub reward_vault_token: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = treasury_token_account.owner == staking_pool.treasury @ StakingError::InvalidTokenAccount,
+        constraint = treasury_token_account.mint == stake_token_account.mint @ StakingError::MintMismatch,
+    )]
+    pub treasury_token_account: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
 }
 
 #[derive(Accounts)]
-pub struct CreateStakeAccount<'info> {
+pub struct RequestUnstake<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"staking_pool",
+            staking_pool.stake_mint.as_ref(),
+            &staking_pool.pool_id.to_le_bytes(),
+        ],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        mut,
+        seeds = [b"staker", staking_pool.key().as_ref(), user.key().as_ref()],
+        bump = staker_info.bump,
+        constraint = staker_info.owner == user.key() @ StakingError::UnauthorizedAccess,
+    )]
+    pub staker_info: Account<'info, StakerInfo>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"position",
+            staker_info.key().as_ref(),
+            &stake_position.index.to_le_bytes(),
+        ],
+        bump = stake_position.bump,
+    )]
+    pub stake_position: Account<'info, StakePosition>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawUnbonded<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"staking_pool",
+            staking_pool.stake_mint.as_ref(),
+            &staking_pool.pool_id.to_le_bytes(),
+        ],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        mut,
+        seeds = [b"staker", staking_pool.key().as_ref(), user.key().as_ref()],
+        bump = staker_info.bump,
+        constraint = staker_info.owner == user.key() @ StakingError::UnauthorizedAccess,
+    )]
+    pub staker_info: Account<'info, StakerInfo>,
+    
+    pub user: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = user_token_account.owner == user.key() @ StakingError::UnauthorizedAccess,
+        constraint = user_token_account.mint == staking_pool.stake_mint @ StakingError::MintMismatch,
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = stake_token_account.key() == staking_pool.stake_vault @ StakingError::InvalidTokenAccount
+    )]
+    pub stake_token_account: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CancelUnbonding<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"staking_pool",
+            staking_pool.stake_mint.as_ref(),
+            &staking_pool.pool_id.to_le_bytes(),
+        ],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        mut,
+        seeds = [b"staker", staking_pool.key().as_ref(), user.key().as_ref()],
+        bump = staker_info.bump,
+        constraint = staker_info.owner == user.key() @ StakingError::UnauthorizedAccess,
+    )]
+    pub staker_info: Account<'info, StakerInfo>,
+    
     #[account(
         init,
-        payer = owner,
-        space = 8 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 1 + 1 + 8 + 8 + 8 + 8 + 8 + 1,
-        seeds = [b"staker", owner.key().as_ref()],
+        payer = user,
+        space = 8 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 1 + 8 + 1,
+        seeds = [
+            b"position",
+            staker_info.key().as_ref(),
+            &staker_info.position_count.to_le_bytes(),
+        ],
         bump
     )]
-    pub staker_info: Account<'info, StakerInfo>,
+    pub stake_position: Account<'info, StakePosition>,
     
     #[account(mut)]
-    pub owner: Signer<'info>,
+    pub user: Signer<'info>,
     
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct Stake<'info> {
-    #[account(mut)]
+pub struct ExtendLock<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"staking_pool",
+            staking_pool.stake_mint.as_ref(),
+            &staking_pool.pool_id.to_le_bytes(),
+        ],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        mut,
+        seeds = [b"staker", staking_pool.key().as_ref(), user.key().as_ref()],
+        bump = staker_info.bump,
+        constraint = staker_info.owner == user.key() @ StakingError::UnauthorizedAccess,
+    )]
+    pub staker_info: Account<'info, StakerInfo>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"position",
+            staker_info.key().as_ref(),
+            &stake_position.index.to_le_bytes(),
+        ],
+        bump = stake_position.bump,
+    )]
+    pub stake_position: Account<'info, StakePosition>,
+    
+    pub user: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimRewards<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"staking_pool",
+            staking_pool.stake_mint.as_ref(),
+            &staking_pool.pool_id.to_le_bytes(),
+        ],
+        bump = staking_pool.bump,
+    )]
     pub staking_pool: Account<'info, StakingPool>,
     
     #[account(
         mut,
-        seeds = [b"staker", user.key().as_ref()],
+        seeds = [b"staker", staking_pool.key().as_ref(), user.key().as_ref()],
         bump = staker_info.bump,
-        has_one = owner @ StakingError::UnauthorizedAccess,
+        constraint = staker_info.owner == user.key() @ StakingError::UnauthorizedAccess,
     )]
     pub staker_info: Account<'info, StakerInfo>,
     
@@ -60,90 +862,204 @@ pub struct Stake<'info> {
     
     #[account(
         mut,
-        constraint = user_token_account.owner == user.key() @ StakingError::UnauthorizedAccess
+        constraint = user_token_account.owner == user.key() @ StakingError::UnauthorizedAccess,
+        constraint = user_token_account.mint == reward_vault.reward_mint @ StakingError::MintMismatch,
     )]
     pub user_token_account: Account<'info, TokenAccount>,
     
     #[account(
         mut,
-        constraint = stake_token_account.owner == staking_pool.key() @ StakingError::InvalidTokenAccount
+        seeds = [
+            b"reward_vault",
+            staking_pool.key().as_ref(),
+            &[reward_vault.index],
+        ],
+        bump = reward_vault.bump,
     )]
-    pub stake_token_account: Account<'info, TokenAccount>,
-    
-    #[account(mut)]
     pub reward_vault: Account<'info, RewardVault>,
     
+    #[account(
+        mut,
+        constraint = reward_vault_token.key() == reward_vault.token_account @ StakingError::InvalidTokenAccount
+    )]
+    pub reward_vault_token: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = treasury_account.owner == staking_pool.treasury @ StakingError::InvalidTokenAccount,
+        constraint = treasury_account.mint == reward_vault.reward_mint @ StakingError::MintMismatch,
+    )]
+    pub treasury_account: Account<'info, TokenAccount>,
+    
     pub token_program: Program<'info, Token>,
 }
 
 #[derive(Accounts)]
-pub struct Unstake<'info> {
-    #[account(mut)]
+pub struct ClaimAllRewards<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"staking_pool",
+            staking_pool.stake_mint.as_ref(),
+            &staking_pool.pool_id.to_le_bytes(),
+        ],
+        bump = staking_pool.bump,
+    )]
     pub staking_pool: Account<'info, StakingPool>,
     
     #[account(
         mut,
-        seeds = [b"staker", user.key().as_ref()],
+        seeds = [b"staker", staking_pool.key().as_ref(), user.key().as_ref()],
         bump = staker_info.bump,
-        has_one = owner @ StakingError::UnauthorizedAccess,
+        constraint = staker_info.owner == user.key() @ StakingError::UnauthorizedAccess,
     )]
     pub staker_info: Account<'info, StakerInfo>,
     
     #[account(mut)]
     pub user: Signer<'info>,
     
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CompoundRewards<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"staking_pool",
+            staking_pool.stake_mint.as_ref(),
+            &staking_pool.pool_id.to_le_bytes(),
+        ],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
     #[account(
         mut,
-        constraint = user_token_account.owner == user.key() @ StakingError::UnauthorizedAccess
+        seeds = [b"staker", staking_pool.key().as_ref(), user.key().as_ref()],
+        bump = staker_info.bump,
+        constraint = staker_info.owner == user.key() @ StakingError::UnauthorizedAccess,
     )]
-    pub user_token_account: Account<'info, TokenAccount>,
+    pub staker_info: Account<'info, StakerInfo>,
     
     #[account(
         mut,
-        constraint = stake_token_account.owner == staking_pool.key() @ StakingError::InvalidTokenAccount
+        seeds = [
+            b"position",
+            staker_info.key().as_ref(),
+            &stake_position.index.to_le_bytes(),
+        ],
+        bump = stake_position.bump,
+    )]
+    pub stake_position: Account<'info, StakePosition>,
+    
+    pub user: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = stake_token_account.key() == staking_pool.stake_vault @ StakingError::InvalidTokenAccount
     )]
     pub stake_token_account: Account<'info, TokenAccount>,
     
     #[account(mut)]
-    pub reward_vault: Account<'info, RewardVault>,
+    pub reward_vault_token: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = treasury_account.owner == staking_pool.treasury @ StakingError::InvalidTokenAccount,
+        constraint = treasury_account.mint == staking_pool.stake_mint @ StakingError::MintMismatch,
+    )]
+    pub treasury_account: Account<'info, TokenAccount>,
     
     pub token_program: Program<'info, Token>,
 }
 
 #[derive(Accounts)]
-pub struct ClaimRewards<'info> {
-    #[account(mut)]
+pub struct SetAutoCompound<'info> {
+    #[account(
+        seeds = [
+            b"staking_pool",
+            staking_pool.stake_mint.as_ref(),
+            &staking_pool.pool_id.to_le_bytes(),
+        ],
+        bump = staking_pool.bump,
+    )]
     pub staking_pool: Account<'info, StakingPool>,
     
     #[account(
         mut,
-        seeds = [b"staker", user.key().as_ref()],
+        seeds = [b"staker", staking_pool.key().as_ref(), user.key().as_ref()],
         bump = staker_info.bump,
-        has_one = owner @ StakingError::UnauthorizedAccess,
+        constraint = staker_info.owner == user.key() @ StakingError::UnauthorizedAccess,
     )]
     pub staker_info: Account<'info, StakerInfo>,
     
-    #[account(mut)]
+    #[account(
+        seeds = [
+            b"position",
+            staker_info.key().as_ref(),
+            &stake_position.index.to_le_bytes(),
+        ],
+        bump = stake_position.bump,
+    )]
+    pub stake_position: Account<'info, StakePosition>,
+    
     pub user: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CrankCompound<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"staking_pool",
+            staking_pool.stake_mint.as_ref(),
+            &staking_pool.pool_id.to_le_bytes(),
+        ],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
     
     #[account(
         mut,
-        constraint = user_token_account.owner == user.key() @ StakingError::UnauthorizedAccess
+        seeds = [b"staker", staking_pool.key().as_ref(), staker_info.owner.as_ref()],
+        bump = staker_info.bump,
     )]
-    pub user_token_account: Account<'info, TokenAccount>,
+    pub staker_info: Account<'info, StakerInfo>,
     
-    #[account(mut)]
-    pub reward_vault: Account<'info, RewardVault>,
+    #[account(
+        mut,
+        seeds = [
+            b"position",
+            staker_info.key().as_ref(),
+            &staker_info.auto_compound_position.to_le_bytes(),
+        ],
+        bump = stake_position.bump,
+    )]
+    pub stake_position: Account<'info, StakePosition>,
+    
+    pub keeper: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = keeper_token_account.owner == keeper.key() @ StakingError::InvalidTokenAccount,
+        constraint = keeper_token_account.mint == staking_pool.stake_mint @ StakingError::MintMismatch,
+    )]
+    pub keeper_token_account: Account<'info, TokenAccount>,
     
     #[account(
         mut,
-        constraint = reward_vault_token.owner == reward_vault.key() @ StakingError::InvalidTokenAccount
+        constraint = stake_token_account.key() == staking_pool.stake_vault @ StakingError::InvalidTokenAccount
     )]
+    pub stake_token_account: Account<'info, TokenAccount>,
+    
+    #[account(mut)]
     pub reward_vault_token: Account<'info, TokenAccount>,
     
     #[account(
         mut,
-        constraint = treasury_account.owner == staking_pool.treasury @ StakingError::InvalidTokenAccount
+        constraint = treasury_account.owner == staking_pool.treasury @ StakingError::InvalidTokenAccount,
+        constraint = treasury_account.mint == staking_pool.stake_mint @ StakingError::MintMismatch,
     )]
     pub treasury_account: Account<'info, TokenAccount>,
     
@@ -154,18 +1070,35 @@ pub struct ClaimRewards<'info> {
 pub struct EmergencyUnstake<'info> {
     #[account(
         mut,
+        seeds = [
+            b"staking_pool",
+            staking_pool.stake_mint.as_ref(),
+            &staking_pool.pool_id.to_le_bytes(),
+        ],
+        bump = staking_pool.bump,
         has_one = emergency_admin @ StakingError::UnauthorizedAccess
     )]
     pub staking_pool: Account<'info, StakingPool>,
     
     #[account(
         mut,
-        seeds = [b"staker", user.key().as_ref()],
+        seeds = [b"staker", staking_pool.key().as_ref(), user.key().as_ref()],
         bump = staker_info.bump,
-        has_one = owner @ StakingError::UnauthorizedAccess,
+        constraint = staker_info.owner == user.key() @ StakingError::UnauthorizedAccess,
     )]
     pub staker_info: Account<'info, StakerInfo>,
     
+    #[account(
+        mut,
+        seeds = [
+            b"position",
+            staker_info.key().as_ref(),
+            &stake_position.index.to_le_bytes(),
+        ],
+        bump = stake_position.bump,
+    )]
+    pub stake_position: Account<'info, StakePosition>,
+    
     #[account(mut)]
     pub user: Signer<'info>,
     
@@ -173,15 +1106,112 @@ pub struct EmergencyUnstake<'info> {
     
     #[account(
         mut,
-        constraint = user_token_account.owner == user.key() @ StakingError::UnauthorizedAccess
+        constraint = user_token_account.owner == user.key() @ StakingError::UnauthorizedAccess,
+        constraint = user_token_account.mint == staking_pool.stake_mint @ StakingError::MintMismatch,
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = stake_token_account.key() == staking_pool.stake_vault @ StakingError::InvalidTokenAccount
+    )]
+    pub stake_token_account: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SetEmergencyMode<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"staking_pool",
+            staking_pool.stake_mint.as_ref(),
+            &staking_pool.pool_id.to_le_bytes(),
+        ],
+        bump = staking_pool.bump,
+        has_one = emergency_admin @ StakingError::UnauthorizedAccess
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    pub emergency_admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct EmergencyWithdraw<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"staking_pool",
+            staking_pool.stake_mint.as_ref(),
+            &staking_pool.pool_id.to_le_bytes(),
+        ],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        mut,
+        seeds = [b"staker", staking_pool.key().as_ref(), user.key().as_ref()],
+        bump = staker_info.bump,
+        constraint = staker_info.owner == user.key() @ StakingError::UnauthorizedAccess,
+    )]
+    pub staker_info: Account<'info, StakerInfo>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"position",
+            staker_info.key().as_ref(),
+            &stake_position.index.to_le_bytes(),
+        ],
+        bump = stake_position.bump,
+    )]
+    pub stake_position: Account<'info, StakePosition>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = user_token_account.owner == user.key() @ StakingError::UnauthorizedAccess,
+        constraint = user_token_account.mint == staking_pool.stake_mint @ StakingError::MintMismatch,
     )]
     pub user_token_account: Account<'info, TokenAccount>,
     
     #[account(
         mut,
-        constraint = stake_token_account.owner == staking_pool.key() @ StakingError::InvalidTokenAccount
+        constraint = stake_token_account.key() == staking_pool.stake_vault @ StakingError::InvalidTokenAccount
     )]
     pub stake_token_account: Account<'info, TokenAccount>,
     
     pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct GetPoolStats<'info> {
+    #[account(
+        seeds = [
+            b"staking_pool",
+            staking_pool.stake_mint.as_ref(),
+            &staking_pool.pool_id.to_le_bytes(),
+        ],
+        bump = staking_pool.bump,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        seeds = [
+            b"reward_vault",
+            staking_pool.key().as_ref(),
+            &[reward_vault.index],
+        ],
+        bump = reward_vault.bump,
+    )]
+    pub reward_vault: Account<'info, RewardVault>,
+    
+    #[account(
+        constraint = reward_vault_token.key() == reward_vault.token_account @ StakingError::InvalidTokenAccount
+    )]
+    pub reward_vault_token: Account<'info, TokenAccount>,
 }
\ No newline at end of file
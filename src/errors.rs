@@ -8,24 +8,126 @@ pub enum StakingError {
     #[msg("Lock period is shorter than minimum required duration")]
     LockPeriodTooShort,
 
+    #[msg("Lock period is longer than maximum allowed duration")]
+    LockPeriodTooLong,
+
+    #[msg("Extended lock must end after the current unlock time")]
+    LockExtensionTooShort,
+
     #[msg("Staking pool is currently paused")]
     PoolPaused,
 
+    #[msg("Staking pool is not paused")]
+    PoolNotPaused,
+
     #[msg("Calculation resulted in overflow")]
     CalculationOverflow,
 
     #[msg("Unauthorized access")]
     UnauthorizedAccess,
 
+    #[msg("No admin transfer is pending for this role")]
+    NoPendingAdminTransfer,
+
+    #[msg("Signer is not the pending admin for this role")]
+    InvalidPendingAdmin,
+
+    #[msg("This change must be queued through the admin timelock")]
+    TimelockRequired,
+
+    #[msg("Admin action timelock has not expired yet")]
+    TimelockNotExpired,
+
+    #[msg("Admin action grace period has passed")]
+    AdminActionExpired,
+
+    #[msg("Admin action was already executed or cancelled")]
+    AdminActionFinalized,
+
+    #[msg("Invalid admin timelock delay")]
+    InvalidTimelockDelay,
+
+    #[msg("Invalid admin signer set or threshold")]
+    InvalidAdminSet,
+
+    #[msg("Admin set account is missing or does not match the pool")]
+    InvalidAdminSetAccount,
+
+    #[msg("Not enough admin signatures to reach the threshold")]
+    InsufficientAdminApprovals,
+
     #[msg("Invalid token account")]
     InvalidTokenAccount,
 
     #[msg("Stake is still locked")]
     StakeLocked,
 
+    #[msg("Pool requires unstaking through the unbonding queue")]
+    UnbondingRequired,
+
+    #[msg("No stake is currently unbonding")]
+    NoUnbondingStake,
+
+    #[msg("Unbonding cooldown has not finished")]
+    UnbondingNotComplete,
+
+    #[msg("Invalid unbonding period")]
+    InvalidUnbondingPeriod,
+
+    #[msg("Stake is already unlocked")]
+    StakeNotLocked,
+
+    #[msg("Invalid early unstake penalty")]
+    InvalidEarlyUnstakePenalty,
+
     #[msg("Insufficient stake balance")]
     InsufficientStakeBalance,
 
+    #[msg("Stake and reward mints do not match")]
+    MintMismatch,
+
+    #[msg("Stake account still holds stake, rewards or open positions")]
+    StakeAccountNotEmpty,
+
+    #[msg("Invalid or out of order reward stream accounts")]
+    InvalidRewardStream,
+
+    #[msg("Pool already has the maximum number of reward streams")]
+    TooManyRewardStreams,
+
+    #[msg("Reward end time must be after the start time")]
+    InvalidRewardWindow,
+
+    #[msg("Reward campaign has not ended yet")]
+    RewardPeriodActive,
+
+    #[msg("No undistributed rewards to recover")]
+    NoLeftoverRewards,
+
+    #[msg("A reward campaign cannot lower the rate of a running stream")]
+    RewardRateDecrease,
+
+    #[msg("Reward vault balance does not cover this reward rate")]
+    InsufficientRewardFunding,
+
+    #[msg("Reward vault has no tokens left to pay out")]
+    RewardVaultDepleted,
+
+    #[msg("Pool is not in emergency mode")]
+    EmergencyModeNotActive,
+
+    #[msg("Pool is in emergency mode")]
+    EmergencyModeActive,
+
+    #[msg("Staker has not opted in to auto-compounding")]
+    AutoCompoundDisabled,
+
+    #[msg("Minimum compound interval has not elapsed")]
+    CompoundTooSoon,
+
+    #[msg("Invalid keeper fee or compound interval")]
+    InvalidKeeperFee,
+
     #[msg("No rewards to claim")]
     NoRewardsToClaim,
 
@@ -46,4 +148,7 @@ pub enum StakingError {
 
     #[msg("Invalid reward rate")]
     InvalidRewardRate,
+
+    #[msg("Amount must be greater than zero")]
+    InvalidAmount,
 }
\ No newline at end of file
@@ -1,7 +1,11 @@
 use anchor_lang::prelude::*;
+use crate::state::AdminAction;
 
 #[event]
 pub struct PoolInitializedEvent {
+    pub pool: Pubkey,
+    pub stake_mint: Pubkey,
+    pub pool_id: u64,
     pub authority: Pubkey,
     pub treasury: Pubkey,
     pub launch_timestamp: i64,
@@ -9,13 +13,23 @@ pub struct PoolInitializedEvent {
 
 #[event]
 pub struct StakeAccountCreatedEvent {
+    pub pool: Pubkey,
     pub owner: Pubkey,
     pub delegated_authority: Option<Pubkey>,
 }
 
+#[event]
+pub struct StakeAccountClosedEvent {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub timestamp: i64,
+}
+
 #[event]
 pub struct StakeEvent {
+    pub pool: Pubkey,
     pub user: Pubkey,
+    pub position: u64,
     pub amount: u64,
     pub timestamp: i64,
     pub tier: u8,
@@ -24,39 +38,278 @@ pub struct StakeEvent {
 
 #[event]
 pub struct UnstakeEvent {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub position: u64,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EarlyUnstakeEvent {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub position: u64,
+    pub amount: u64,
+    pub penalty: u64,
+    pub redistributed: bool,
+    pub release_time: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct UnstakeRequestedEvent {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub position: u64,
+    pub amount: u64,
+    pub unbonding_amount: u64,
+    pub release_time: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct UnbondingWithdrawnEvent {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct UnbondingCancelledEvent {
+    pub pool: Pubkey,
     pub user: Pubkey,
+    pub position: u64,
     pub amount: u64,
+    pub lock_period: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LockExtendedEvent {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub position: u64,
+    pub previous_unlock_time: i64,
+    pub unlock_time: i64,
+    pub lock_period: i64,
+    pub lock_bonus: u64,
     pub timestamp: i64,
 }
 
 #[event]
 pub struct RewardsClaimedEvent {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub stream: u8,
+    pub amount: u64,
+    pub treasury_fee: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RewardsCompoundedEvent {
+    pub pool: Pubkey,
     pub user: Pubkey,
+    pub stream: u8,
+    pub position: u64,
     pub amount: u64,
     pub treasury_fee: u64,
+    pub tier: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AutoCompoundUpdatedEvent {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub enabled: bool,
+    pub position: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CompoundCrankedEvent {
+    pub pool: Pubkey,
+    pub keeper: Pubkey,
+    pub user: Pubkey,
+    pub keeper_bounty: u64,
     pub timestamp: i64,
 }
 
 #[event]
 pub struct EmergencyUnstakeEvent {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub position: u64,
+    pub amount: u64,
+    pub remaining: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EmergencyModeUpdatedEvent {
+    pub pool: Pubkey,
+    pub emergency_admin: Pubkey,
+    pub enabled: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EmergencyWithdrawEvent {
+    pub pool: Pubkey,
     pub user: Pubkey,
+    pub position: u64,
     pub amount: u64,
     pub timestamp: i64,
 }
 
 #[event]
 pub struct StakingPoolConfigUpdatedEvent {
+    pub pool: Pubkey,
     pub authority: Pubkey,
     pub min_stake_duration: i64,
     pub max_stake_duration: i64,
     pub rewards_multiplier: u64,
     pub treasury_fee: u64,
+    pub unbonding_period: i64,
     pub timestamp: i64,
 }
 
 #[event]
 pub struct RewardVaultUpdatedEvent {
+    pub pool: Pubkey,
+    pub authority: Pubkey,
+    pub stream: u8,
+    pub previous_rewards_per_second: u64,
+    pub rewards_per_second: u64,
+    pub accumulated_rewards_per_share: u128,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RewardVaultInitializedEvent {
+    pub pool: Pubkey,
+    pub authority: Pubkey,
+    pub stream: u8,
+    pub reward_mint: Pubkey,
+    pub token_account: Pubkey,
+    pub rewards_per_second: u64,
+    pub reward_start_time: i64,
+    pub reward_end_time: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RewardVaultFundedEvent {
+    pub pool: Pubkey,
+    pub authority: Pubkey,
+    pub stream: u8,
+    pub amount: u64,
+    pub vault_balance: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RewardAmountNotifiedEvent {
+    pub pool: Pubkey,
     pub authority: Pubkey,
+    pub stream: u8,
+    pub amount: u64,
     pub rewards_per_second: u64,
+    pub reward_start_time: i64,
+    pub reward_end_time: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RewardVaultDepletedEvent {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub stream: u8,
+    pub owed: u64,
+    pub paid: u64,
+    pub vault_balance: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LeftoverRewardsRecoveredEvent {
+    pub pool: Pubkey,
+    pub authority: Pubkey,
+    pub stream: u8,
+    pub amount: u64,
+    pub destination: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PoolPausedEvent {
+    pub pool: Pubkey,
+    pub admin: Pubkey,
+    pub reason: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PoolResumedEvent {
+    pub pool: Pubkey,
+    pub admin: Pubkey,
+    pub paused_duration: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AdminTransferProposedEvent {
+    pub pool: Pubkey,
+    pub role: u8,
+    pub current: Pubkey,
+    pub proposed: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AdminTransferAcceptedEvent {
+    pub pool: Pubkey,
+    pub role: u8,
+    pub previous: Pubkey,
+    pub new_admin: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AdminActionQueuedEvent {
+    pub pool: Pubkey,
+    pub proposal: Pubkey,
+    pub id: u64,
+    pub action: AdminAction,
+    pub eta: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AdminActionCancelledEvent {
+    pub pool: Pubkey,
+    pub proposal: Pubkey,
+    pub id: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AdminActionExecutedEvent {
+    pub pool: Pubkey,
+    pub proposal: Pubkey,
+    pub id: u64,
+    pub executor: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AdminSetUpdatedEvent {
+    pub pool: Pubkey,
+    pub admin_set: Pubkey,
+    pub signers: Vec<Pubkey>,
+    pub threshold: u8,
     pub timestamp: i64,
 }
\ No newline at end of file
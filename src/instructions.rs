@@ -2,30 +2,59 @@ use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Token, TokenAccount, Transfer};
 use std::convert::TryFrom;
 use crate::state::*;
+use crate::context::*;
 use crate::errors::*;
 use crate::events::*;
+use crate::{PoolConfig, PoolConfigUpdate, PoolStats};
 
 const MIN_STAKE_AMOUNT: u64 = 100_000_000; // 100 GENT (with 6 decimals)
 const REWARDS_PRECISION: u128 = 1_000_000_000_000;
 const SECONDS_PER_DAY: i64 = 86400;
+const BASIS_POINTS: u64 = 10000;
+const MAX_REWARDS_MULTIPLIER: u64 = 50_000; // 5x
+const MAX_REWARDS_PER_SECOND: u64 = 1_000_000_000; // 1,000 GENT per second
+const MAX_ADMIN_TIMELOCK_DELAY: i64 = 30 * SECONDS_PER_DAY;
+const ADMIN_ACTION_GRACE_PERIOD: i64 = 14 * SECONDS_PER_DAY;
+const MAX_UNBONDING_PERIOD: i64 = 30 * SECONDS_PER_DAY;
+const MAX_EARLY_UNSTAKE_PENALTY: u64 = 5000; // 50%
+const MAX_KEEPER_FEE: u64 = 500; // 5%
+const CLAIM_ACCOUNTS_PER_STREAM: usize = 4;
+const MIN_REWARD_RUNWAY: i64 = 7 * SECONDS_PER_DAY; // Funding kept ahead of open-ended streams
 
 pub fn initialize_pool(
     ctx: Context<InitializePool>,
+    pool_id: u64,
     config: PoolConfig,
 ) -> Result<()> {
+    validate_pool_config(&config)?;
+
     let pool = &mut ctx.accounts.staking_pool;
     pool.authority = ctx.accounts.authority.key();
     pool.treasury = ctx.accounts.treasury.key();
     pool.emergency_admin = ctx.accounts.emergency_admin.key();
+    pool.stake_mint = ctx.accounts.stake_mint.key();
+    pool.pool_id = pool_id;
+    pool.stake_vault = ctx.accounts.stake_vault.key();
     pool.launch_timestamp = Clock::get()?.unix_timestamp;
-    pool.early_adopter_deadline = pool.launch_timestamp + config.early_adopter_period;
+    pool.early_adopter_deadline = pool.launch_timestamp
+        .checked_add(config.early_adopter_period)
+        .ok_or(StakingError::CalculationOverflow)?;
     pool.min_stake_duration = config.min_stake_duration;
     pool.max_stake_duration = config.max_stake_duration;
     pool.rewards_multiplier = config.rewards_multiplier;
     pool.treasury_fee = config.treasury_fee;
+    pool.admin_timelock_delay = config.admin_timelock_delay;
+    pool.unbonding_period = config.unbonding_period;
+    pool.early_unstake_penalty = config.early_unstake_penalty;
+    pool.penalty_destination = config.penalty_destination;
+    pool.keeper_fee = config.keeper_fee;
+    pool.min_compound_interval = config.min_compound_interval;
     pool.bump = *ctx.bumps.get("staking_pool").unwrap();
 
     emit!(PoolInitializedEvent {
+        pool: pool.key(),
+        stake_mint: pool.stake_mint,
+        pool_id,
         authority: pool.authority,
         treasury: pool.treasury,
         launch_timestamp: pool.launch_timestamp,
@@ -34,329 +63,2814 @@ pub fn initialize_pool(
     Ok(())
 }
 
-pub fn create_stake_account(
-    ctx: Context<CreateStakeAccount>,
-    delegated_authority: Option<Pubkey>,
+// Adds a reward stream to the pool. Each stream has its own mint, vault and
+// emission schedule; stakers earn from every stream in proportion to their
+// stake weight. `funding` is deposited from the authority before the stream
+// starts, so it can be created emitting at a rate the deposit covers.
+pub fn initialize_reward_vault(
+    ctx: Context<InitializeRewardVault>,
+    rewards_per_second: u64,
+    reward_start_time: i64,
+    reward_end_time: i64,
+    funding: u64,
 ) -> Result<()> {
-    let staker = &mut ctx.accounts.staker_info;
-    staker.owner = ctx.accounts.owner.key();
-    staker.delegated_authority = delegated_authority;
-    staker.bump = *ctx.bumps.get("staker_info").unwrap();
-
-    emit!(StakeAccountCreatedEvent {
-        owner: staker.owner,
-        delegated_authority,
-    });
+    require_admin_approval(
+        &ctx.accounts.staking_pool,
+        &ctx.accounts.authority.to_account_info(),
+        ctx.remaining_accounts,
+    )?;
 
-    Ok(())
-}
+    validate_reward_rate(rewards_per_second)?;
+    validate_reward_window(reward_start_time, reward_end_time)?;
 
-pub fn stake(
-    ctx: Context<Stake>,
-    amount: u64,
-    lock_period: i64,
-) -> Result<()> {
-    require!(amount >= MIN_STAKE_AMOUNT, StakingError::BelowMinimumStake);
+    let pool = &mut ctx.accounts.staking_pool;
     require!(
-        lock_period >= ctx.accounts.staking_pool.min_stake_duration,
-        StakingError::LockPeriodTooShort
+        (pool.reward_stream_count as usize) < MAX_REWARD_STREAMS,
+        StakingError::TooManyRewardStreams
     );
 
-    let pool = &ctx.accounts.staking_pool;
-    require!(!pool.paused, StakingError::PoolPaused);
-
-    let staker = &mut ctx.accounts.staker_info;
+    let vault = &mut ctx.accounts.reward_vault;
     let clock = Clock::get()?;
 
-    // Update rewards before new stake
-    if staker.amount > 0 {
-        let pending_rewards = calculate_pending_rewards(
-            &ctx.accounts.reward_vault,
-            staker,
-            clock.unix_timestamp,
+    vault.pool = pool.key();
+    vault.index = pool.reward_stream_count;
+    vault.authority = ctx.accounts.authority.key();
+    vault.reward_mint = ctx.accounts.reward_mint.key();
+    vault.token_account = ctx.accounts.reward_vault_token.key();
+    vault.rewards_per_second = rewards_per_second;
+    vault.reward_start_time = reward_start_time;
+    vault.reward_end_time = reward_end_time;
+    vault.last_update_time = clock.unix_timestamp;
+    vault.accumulated_rewards_per_share = 0;
+    vault.bump = *ctx.bumps.get("reward_vault").unwrap();
+
+    if funding > 0 {
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.funder_token_account.to_account_info(),
+                    to: ctx.accounts.reward_vault_token.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info(),
+                },
+            ),
+            funding,
         )?;
-        staker.accumulated_rewards = staker.accumulated_rewards
-            .checked_add(pending_rewards)
-            .ok_or(StakingError::CalculationOverflow)?;
+
+        ctx.accounts.reward_vault_token.reload()?;
     }
 
-    // Transfer tokens to stake account
-    token::transfer(
-        CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.user_token_account.to_account_info(),
-                to: ctx.accounts.stake_token_account.to_account_info(),
-                authority: ctx.accounts.user.to_account_info(),
-            },
-        ),
-        amount,
+    validate_reward_runway(
+        vault,
+        pool,
+        rewards_per_second,
+        ctx.accounts.reward_vault_token.amount,
+        clock.unix_timestamp,
     )?;
 
-    // Update staker info
-    staker.amount = staker.amount
-        .checked_add(amount)
-        .ok_or(StakingError::CalculationOverflow)?;
-    staker.start_time = clock.unix_timestamp;
-    staker.last_reward_time = clock.unix_timestamp;
-    staker.tier = calculate_tier(staker.amount);
-    staker.is_early_adopter = clock.unix_timestamp <= pool.early_adopter_deadline;
-    staker.lock_period = lock_period;
-    staker.unlock_time = clock.unix_timestamp
-        .checked_add(lock_period)
-        .ok_or(StakingError::CalculationOverflow)?;
-
-    // Update pool stats
-    let pool = &mut ctx.accounts.staking_pool;
-    pool.total_staked = pool.total_staked
-        .checked_add(amount)
-        .ok_or(StakingError::CalculationOverflow)?;
-    pool.stake_count = pool.stake_count
+    pool.reward_stream_count = pool.reward_stream_count
         .checked_add(1)
         .ok_or(StakingError::CalculationOverflow)?;
 
-    emit!(StakeEvent {
-        user: ctx.accounts.user.key(),
-        amount,
+    emit!(RewardVaultInitializedEvent {
+        pool: ctx.accounts.staking_pool.key(),
+        authority: vault.authority,
+        stream: vault.index,
+        reward_mint: vault.reward_mint,
+        token_account: vault.token_account,
+        rewards_per_second,
+        reward_start_time,
+        reward_end_time,
         timestamp: clock.unix_timestamp,
-        tier: staker.tier,
-        lock_period,
     });
 
+    if funding > 0 {
+        emit!(RewardVaultFundedEvent {
+            pool: ctx.accounts.staking_pool.key(),
+            authority: ctx.accounts.authority.key(),
+            stream: ctx.accounts.reward_vault.index,
+            amount: funding,
+            vault_balance: ctx.accounts.reward_vault_token.amount,
+            timestamp: clock.unix_timestamp,
+        });
+    }
+
     Ok(())
 }
 
-pub fn unstake(
-    ctx: Context<Unstake>,
+pub fn fund_reward_vault(
+    ctx: Context<FundRewardVault>,
     amount: u64,
 ) -> Result<()> {
-    let staker = &mut ctx.accounts.staker_info;
-    let clock = Clock::get()?;
-
-    require!(
-        clock.unix_timestamp >= staker.unlock_time,
-        StakingError::StakeLocked
-    );
-    require!(amount <= staker.amount, StakingError::InsufficientStakeBalance);
-
-    // Claim any pending rewards before unstaking
-    let pending_rewards = calculate_pending_rewards(
-        &ctx.accounts.reward_vault,
-        staker,
-        clock.unix_timestamp,
+    require_admin_approval(
+        &ctx.accounts.staking_pool,
+        &ctx.accounts.authority.to_account_info(),
+        ctx.remaining_accounts,
     )?;
 
-    if pending_rewards > 0 {
-        staker.accumulated_rewards = staker.accumulated_rewards
-            .checked_add(pending_rewards)
-            .ok_or(StakingError::CalculationOverflow)?;
-    }
+    require!(amount > 0, StakingError::InvalidAmount);
 
-    // Transfer unstaked tokens back to user
+    // Transfer reward tokens into the vault
     token::transfer(
-        CpiContext::new_with_signer(
+        CpiContext::new(
             ctx.accounts.token_program.to_account_info(),
             Transfer {
-                from: ctx.accounts.stake_token_account.to_account_info(),
-                to: ctx.accounts.user_token_account.to_account_info(),
-                authority: ctx.accounts.staking_pool.to_account_info(),
+                from: ctx.accounts.funder_token_account.to_account_info(),
+                to: ctx.accounts.reward_vault_token.to_account_info(),
+                authority: ctx.accounts.authority.to_account_info(),
             },
-            &[&[
-                b"staking_pool".as_ref(),
-                &[ctx.accounts.staking_pool.bump],
-            ]],
         ),
         amount,
     )?;
 
-    // Update staker info
-    staker.amount = staker.amount
-        .checked_sub(amount)
-        .ok_or(StakingError::CalculationOverflow)?;
-    staker.last_reward_time = clock.unix_timestamp;
-
-    // Update pool stats
-    let pool = &mut ctx.accounts.staking_pool;
-    pool.total_staked = pool.total_staked
-        .checked_sub(amount)
-        .ok_or(StakingError::CalculationOverflow)?;
+    ctx.accounts.reward_vault_token.reload()?;
 
-    emit!(UnstakeEvent {
-        user: ctx.accounts.user.key(),
+    emit!(RewardVaultFundedEvent {
+        pool: ctx.accounts.staking_pool.key(),
+        authority: ctx.accounts.authority.key(),
+        stream: ctx.accounts.reward_vault.index,
         amount,
-        timestamp: clock.unix_timestamp,
+        vault_balance: ctx.accounts.reward_vault_token.amount,
+        timestamp: Clock::get()?.unix_timestamp,
     });
 
     Ok(())
 }
 
-pub fn claim_rewards(ctx: Context<ClaimRewards>) -> Result<()> {
-    let staker = &mut ctx.accounts.staker_info;
+// Remaining accounts: every reward stream in index order, then the streams'
+// token accounts in the same order, then any admin set approvals
+pub fn update_pool_config(
+    ctx: Context<UpdatePoolConfig>,
+    update: PoolConfigUpdate,
+) -> Result<()> {
     let clock = Clock::get()?;
-
-    let pending_rewards = calculate_pending_rewards(
-        &ctx.accounts.reward_vault,
-        staker,
+    let streams = load_reward_streams(
+        &ctx.accounts.staking_pool,
+        ctx.remaining_accounts,
         clock.unix_timestamp,
     )?;
 
-    let total_rewards = staker.accumulated_rewards
-        .checked_add(pending_rewards)
-        .ok_or(StakingError::CalculationOverflow)?;
+    let balances = load_reward_stream_balances(
+        &streams,
+        &ctx.remaining_accounts[streams.len()..],
+    )?;
 
-    require!(total_rewards > 0, StakingError::NoRewardsToClaim);
+    require_admin_approval(
+        &ctx.accounts.staking_pool,
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.remaining_accounts[streams.len() * 2..],
+    )?;
 
-    // Calculate treasury fee
-    let treasury_fee = total_rewards
-        .checked_mul(ctx.accounts.staking_pool.treasury_fee as u64)
-        .ok_or(StakingError::CalculationOverflow)?
-        .checked_div(10000)
-        .ok_or(StakingError::CalculationOverflow)?;
+    let pool = &mut ctx.accounts.staking_pool;
+    let previous_rewards_multiplier = pool.rewards_multiplier;
 
-    let user_reward = total_rewards
-        .checked_sub(treasury_fee)
-        .ok_or(StakingError::CalculationOverflow)?;
+    // Fee, lock, emission and exit terms hit every staker, so once a delay is
+    // configured they can only change through the admin timelock
+    if pool.admin_timelock_delay > 0 {
+        require!(
+            update.min_stake_duration.is_none()
+                && update.max_stake_duration.is_none()
+                && update.rewards_multiplier.is_none()
+                && update.treasury_fee.is_none()
+                && update.unbonding_period.is_none()
+                && update.early_unstake_penalty.is_none()
+                && update.penalty_destination.is_none()
+                && update.keeper_fee.is_none(),
+            StakingError::TimelockRequired
+        );
+    }
 
-    // Transfer rewards
-    token::transfer(
-        CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.reward_vault_token.to_account_info(),
-                to: ctx.accounts.user_token_account.to_account_info(),
-                authority: ctx.accounts.reward_vault.to_account_info(),
-            },
-            &[&[
-                b"reward_vault".as_ref(),
-                &[ctx.accounts.reward_vault.bump],
-            ]],
-        ),
-        user_reward,
-    )?;
+    let min_stake_duration = update.min_stake_duration.unwrap_or(pool.min_stake_duration);
+    let max_stake_duration = update.max_stake_duration.unwrap_or(pool.max_stake_duration);
+    let rewards_multiplier = update.rewards_multiplier.unwrap_or(pool.rewards_multiplier);
+    let treasury_fee = update.treasury_fee.unwrap_or(pool.treasury_fee);
+    let unbonding_period = update.unbonding_period.unwrap_or(pool.unbonding_period);
+    let early_unstake_penalty = update.early_unstake_penalty.unwrap_or(pool.early_unstake_penalty);
+    let penalty_destination = update.penalty_destination.unwrap_or(pool.penalty_destination);
+    let keeper_fee = update.keeper_fee.unwrap_or(pool.keeper_fee);
+    let min_compound_interval = update.min_compound_interval.unwrap_or(pool.min_compound_interval);
 
-    // Transfer treasury fee
-    if treasury_fee > 0 {
-        token::transfer(
-            CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                Transfer {
-                    from: ctx.accounts.reward_vault_token.to_account_info(),
-                    to: ctx.accounts.treasury_account.to_account_info(),
-                    authority: ctx.accounts.reward_vault.to_account_info(),
-                },
-                &[&[
-                    b"reward_vault".as_ref(),
-                    &[ctx.accounts.reward_vault.bump],
-                ]],
-            ),
-            treasury_fee,
-        )?;
-    }
+    validate_lock_bounds(min_stake_duration, max_stake_duration)?;
+    validate_rewards_multiplier(rewards_multiplier)?;
+    validate_treasury_fee(treasury_fee)?;
+    validate_unbonding_period(unbonding_period)?;
+    validate_early_unstake_penalty(early_unstake_penalty)?;
+    validate_compound_settings(keeper_fee, min_compound_interval)?;
+    validate_fee_split(treasury_fee, keeper_fee)?;
 
-    // Update staker info
-    staker.accumulated_rewards = 0;
-    staker.last_reward_time = clock.unix_timestamp;
-    staker.rewards_claimed = staker.rewards_claimed
-        .checked_add(total_rewards)
-        .ok_or(StakingError::CalculationOverflow)?;
+    // Emissions under the old multiplier were settled when the streams loaded
+    persist_reward_streams(&streams)?;
 
-    emit!(RewardsClaimedEvent {
-        user: ctx.accounts.user.key(),
-        amount: user_reward,
+    pool.min_stake_duration = min_stake_duration;
+    pool.max_stake_duration = max_stake_duration;
+    pool.rewards_multiplier = rewards_multiplier;
+    pool.treasury_fee = treasury_fee;
+    pool.unbonding_period = unbonding_period;
+    pool.early_unstake_penalty = early_unstake_penalty;
+    pool.penalty_destination = penalty_destination;
+    pool.keeper_fee = keeper_fee;
+    pool.min_compound_interval = min_compound_interval;
+
+    // A higher multiplier raises every stream's emissions, so each vault must
+    // still cover its runway at the new multiplier
+    if rewards_multiplier > previous_rewards_multiplier {
+        for (reward_vault, balance) in streams.iter().zip(balances) {
+            validate_reward_runway(
+                reward_vault,
+                pool,
+                reward_vault.rewards_per_second,
+                balance,
+                clock.unix_timestamp,
+            )?;
+        }
+    }
+
+    emit!(StakingPoolConfigUpdatedEvent {
+        pool: ctx.accounts.staking_pool.key(),
+        authority: ctx.accounts.authority.key(),
+        min_stake_duration,
+        max_stake_duration,
+        rewards_multiplier,
         treasury_fee,
+        unbonding_period,
         timestamp: clock.unix_timestamp,
     });
 
     Ok(())
 }
 
-pub fn emergency_unstake(ctx: Context<EmergencyUnstake>) -> Result<()> {
-    let staker = &mut ctx.accounts.staker_info;
-    let amount = staker.amount;
-
-    require!(amount > 0, StakingError::NoStakeToUnstake);
-
-    // Transfer all staked tokens back to user
-    token::transfer(
-        CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.stake_token_account.to_account_info(),
-                to: ctx.accounts.user_token_account.to_account_info(),
-                authority: ctx.accounts.staking_pool.to_account_info(),
-            },
-            &[&[
-                b"staking_pool".as_ref(),
-                &[ctx.accounts.staking_pool.bump],
-            ]],
-        ),
-        amount,
+pub fn queue_admin_action(
+    ctx: Context<QueueAdminAction>,
+    action: AdminAction,
+) -> Result<()> {
+    require_admin_approval(
+        &ctx.accounts.staking_pool,
+        &ctx.accounts.authority.to_account_info(),
+        ctx.remaining_accounts,
     )?;
 
-    // Reset staker info
-    staker.amount = 0;
-    staker.accumulated_rewards = 0;
-    staker.last_reward_time = Clock::get()?.unix_timestamp;
+    validate_admin_action(&action)?;
 
-    // Update pool stats
     let pool = &mut ctx.accounts.staking_pool;
-    pool.total_staked = pool.total_staked
-        .checked_sub(amount)
+    let proposal = &mut ctx.accounts.admin_proposal;
+    let clock = Clock::get()?;
+
+    proposal.pool = pool.key();
+    proposal.proposer = ctx.accounts.authority.key();
+    proposal.id = pool.proposal_count;
+    proposal.action = action;
+    proposal.queued_at = clock.unix_timestamp;
+    proposal.eta = clock.unix_timestamp
+        .checked_add(pool.admin_timelock_delay)
         .ok_or(StakingError::CalculationOverflow)?;
+    proposal.bump = *ctx.bumps.get("admin_proposal").unwrap();
 
-    emit!(EmergencyUnstakeEvent {
-        user: ctx.accounts.user.key(),
-        amount,
+    pool.proposal_count = pool.proposal_count
+        .checked_add(1)
+        .ok_or(StakingError::CalculationOverflow)?;
+
+    emit!(AdminActionQueuedEvent {
+        pool: ctx.accounts.staking_pool.key(),
+        proposal: proposal.key(),
+        id: proposal.id,
+        action,
+        eta: proposal.eta,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub fn cancel_admin_action(ctx: Context<CancelAdminAction>) -> Result<()> {
+    require_admin_approval(
+        &ctx.accounts.staking_pool,
+        &ctx.accounts.authority.to_account_info(),
+        ctx.remaining_accounts,
+    )?;
+
+    let proposal = &mut ctx.accounts.admin_proposal;
+    require!(
+        !proposal.executed && !proposal.cancelled,
+        StakingError::AdminActionFinalized
+    );
+
+    proposal.cancelled = true;
+
+    emit!(AdminActionCancelledEvent {
+        pool: ctx.accounts.staking_pool.key(),
+        proposal: proposal.key(),
+        id: proposal.id,
         timestamp: Clock::get()?.unix_timestamp,
     });
 
     Ok(())
 }
 
-// Helper functions
-fn calculate_pending_rewards(
-    reward_vault: &RewardVault,
-    staker: &StakerInfo,
-    current_time: i64,
-) -> Result<u64> {
-    if staker.amount == 0 || current_time <= staker.last_reward_time {
-        return Ok(0);
-    }
+// Anyone may execute a queued action once its delay has elapsed. A reward
+// rate change takes the stream's reward vault and its token account as
+// remaining accounts; the other actions take none.
+pub fn execute_admin_action<'info>(
+    ctx: Context<'_, '_, '_, 'info, ExecuteAdminAction<'info>>,
+) -> Result<()> {
+    let pool = &mut ctx.accounts.staking_pool;
+    let proposal = &mut ctx.accounts.admin_proposal;
+    let clock = Clock::get()?;
 
-    let time_delta = (current_time - staker.last_reward_time) as u64;
-    let base_rewards = reward_vault.rewards_per_second
-        .checked_mul(time_delta)
-        .ok_or(StakingError::CalculationOverflow)?
-        .checked_mul(staker.amount)
-        .ok_or(StakingError::CalculationOverflow)?;
+    require!(
+        !proposal.executed && !proposal.cancelled,
+        StakingError::AdminActionFinalized
+    );
+    require!(
+        clock.unix_timestamp >= proposal.eta,
+        StakingError::TimelockNotExpired
+    );
+    require!(
+        clock.unix_timestamp <= proposal.eta.saturating_add(ADMIN_ACTION_GRACE_PERIOD),
+        StakingError::AdminActionExpired
+    );
 
-    let mut multiplier = 10000; // Base multiplier (100%)
+    // Bounds may have been tightened by an upgrade while the action was queued
+    validate_admin_action(&proposal.action)?;
 
-    // Add tier bonus
-    multiplier = multiplier
-        .checked_add(get_tier_bonus(staker.tier))
-        .ok_or(StakingError::CalculationOverflow)?;
+    match proposal.action {
+        AdminAction::SetTreasuryFee { treasury_fee } => {
+            validate_fee_split(treasury_fee, pool.keeper_fee)?;
+            pool.treasury_fee = treasury_fee;
+        }
+        AdminAction::SetRewardRate { stream, rewards_per_second } => {
+            let stream_info = ctx.remaining_accounts
+                .first()
+                .ok_or(StakingError::InvalidRewardStream)?;
+            let mut streams = vec![load_reward_stream(pool.key(), stream_info, stream as usize)?];
+            let balances = load_reward_stream_balances(&streams, &ctx.remaining_accounts[1..])?;
 
-    // Add early adopter bonus
-    if staker.is_early_adopter {
-        multiplier = multiplier
-            .checked_add(1000) // 10% bonus
-            .ok_or(StakingError::CalculationOverflow)?;
+            let previous_rewards_per_second = apply_reward_rate(
+                &mut streams[0],
+                pool,
+                rewards_per_second,
+                balances[0],
+                clock.unix_timestamp,
+            )?;
+            persist_reward_streams(&streams)?;
+
+            emit!(RewardVaultUpdatedEvent {
+                pool: pool.key(),
+                authority: proposal.proposer,
+                stream,
+                previous_rewards_per_second,
+                rewards_per_second,
+                accumulated_rewards_per_share: streams[0].accumulated_rewards_per_share,
+                timestamp: clock.unix_timestamp,
+            });
+        }
+        AdminAction::SetLockBounds { min_stake_duration, max_stake_duration } => {
+            pool.min_stake_duration = min_stake_duration;
+            pool.max_stake_duration = max_stake_duration;
+        }
+        AdminAction::SetTimelockDelay { delay } => {
+            pool.admin_timelock_delay = delay;
+        }
     }
 
-    // Add lock period bonus
-    let lock_bonus = calculate_lock_bonus(staker.lock_period)?;
-    multiplier = multiplier
-        .checked_add(lock_bonus)
-        .ok_or(StakingError::CalculationOverflow)?;
+    if matches!(
+        proposal.action,
+        AdminAction::SetTreasuryFee { .. } | AdminAction::SetLockBounds { .. }
+    ) {
+        emit!(StakingPoolConfigUpdatedEvent {
+            pool: pool.key(),
+            authority: proposal.proposer,
+            min_stake_duration: pool.min_stake_duration,
+            max_stake_duration: pool.max_stake_duration,
+            rewards_multiplier: pool.rewards_multiplier,
+            treasury_fee: pool.treasury_fee,
+            unbonding_period: pool.unbonding_period,
+            timestamp: clock.unix_timestamp,
+        });
+    }
+
+    proposal.executed = true;
+
+    emit!(AdminActionExecutedEvent {
+        pool: ctx.accounts.staking_pool.key(),
+        proposal: proposal.key(),
+        id: proposal.id,
+        executor: ctx.accounts.executor.key(),
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub fn initialize_admin_set(
+    ctx: Context<InitializeAdminSet>,
+    signers: Vec<Pubkey>,
+    threshold: u8,
+) -> Result<()> {
+    validate_admin_set(&signers, threshold)?;
+
+    let admin_set = &mut ctx.accounts.admin_set;
+    admin_set.pool = ctx.accounts.staking_pool.key();
+    admin_set.signers = signers;
+    admin_set.threshold = threshold;
+    admin_set.bump = *ctx.bumps.get("admin_set").unwrap();
+
+    let pool = &mut ctx.accounts.staking_pool;
+    pool.admin_set = Some(admin_set.key());
+
+    emit!(AdminSetUpdatedEvent {
+        pool: ctx.accounts.staking_pool.key(),
+        admin_set: admin_set.key(),
+        signers: admin_set.signers.clone(),
+        threshold,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// Signers of the current set are passed as remaining accounts
+pub fn update_admin_set(
+    ctx: Context<UpdateAdminSet>,
+    signers: Vec<Pubkey>,
+    threshold: u8,
+) -> Result<()> {
+    verify_admin_signatures(
+        &ctx.accounts.admin_set,
+        &ctx.accounts.authority.to_account_info(),
+        ctx.remaining_accounts,
+    )?;
+    validate_admin_set(&signers, threshold)?;
+
+    let admin_set = &mut ctx.accounts.admin_set;
+    admin_set.signers = signers;
+    admin_set.threshold = threshold;
+
+    emit!(AdminSetUpdatedEvent {
+        pool: ctx.accounts.staking_pool.key(),
+        admin_set: admin_set.key(),
+        signers: admin_set.signers.clone(),
+        threshold,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
 
-    let rewards = base_rewards
-        .checked_mul(multiplier)
+    Ok(())
+}
+
+// Rotating a privileged key takes two steps: the authority proposes the new
+// key and the new key must sign `accept_admin_transfer` to take over.
+pub fn propose_admin_transfer(
+    ctx: Context<ProposeAdminTransfer>,
+    role: AdminRole,
+    new_admin: Pubkey,
+) -> Result<()> {
+    require_admin_approval(
+        &ctx.accounts.staking_pool,
+        &ctx.accounts.authority.to_account_info(),
+        ctx.remaining_accounts,
+    )?;
+
+    let pool = &mut ctx.accounts.staking_pool;
+    let (current, pending) = admin_role_slots(pool, role);
+    let current = *current;
+    *pending = Some(new_admin);
+
+    emit!(AdminTransferProposedEvent {
+        pool: ctx.accounts.staking_pool.key(),
+        role: role as u8,
+        current,
+        proposed: new_admin,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub fn accept_admin_transfer(
+    ctx: Context<AcceptAdminTransfer>,
+    role: AdminRole,
+) -> Result<()> {
+    let new_admin = ctx.accounts.new_admin.key();
+    let pool = &mut ctx.accounts.staking_pool;
+    let (current, pending) = admin_role_slots(pool, role);
+
+    let proposed = pending.ok_or(StakingError::NoPendingAdminTransfer)?;
+    require_keys_eq!(proposed, new_admin, StakingError::InvalidPendingAdmin);
+
+    let previous = *current;
+    *current = new_admin;
+    *pending = None;
+
+    emit!(AdminTransferAcceptedEvent {
+        pool: ctx.accounts.staking_pool.key(),
+        role: role as u8,
+        previous,
+        new_admin,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// While paused, new stakes and reward claims are blocked. Unstaking and
+// emergency unstaking stay available so stakers can always exit. The
+// emergency admin can act alone; the authority needs admin set approval.
+pub fn pause_pool(
+    ctx: Context<SetPoolPaused>,
+    reason: PauseReason,
+) -> Result<()> {
+    if ctx.accounts.admin.key() != ctx.accounts.staking_pool.emergency_admin {
+        require_admin_approval(
+            &ctx.accounts.staking_pool,
+            &ctx.accounts.admin.to_account_info(),
+            ctx.remaining_accounts,
+        )?;
+    }
+
+    let pool = &mut ctx.accounts.staking_pool;
+    require!(!pool.paused, StakingError::PoolPaused);
+
+    let clock = Clock::get()?;
+    pool.paused = true;
+    pool.pause_reason = reason as u8;
+    pool.paused_at = clock.unix_timestamp;
+
+    emit!(PoolPausedEvent {
+        pool: pool.key(),
+        admin: ctx.accounts.admin.key(),
+        reason: pool.pause_reason,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub fn resume_pool(ctx: Context<SetPoolPaused>) -> Result<()> {
+    if ctx.accounts.admin.key() != ctx.accounts.staking_pool.emergency_admin {
+        require_admin_approval(
+            &ctx.accounts.staking_pool,
+            &ctx.accounts.admin.to_account_info(),
+            ctx.remaining_accounts,
+        )?;
+    }
+
+    let pool = &mut ctx.accounts.staking_pool;
+    require!(pool.paused, StakingError::PoolNotPaused);
+    require!(!pool.emergency_mode, StakingError::EmergencyModeActive);
+
+    let clock = Clock::get()?;
+    let paused_duration = clock.unix_timestamp
+        .checked_sub(pool.paused_at)
+        .ok_or(StakingError::CalculationOverflow)?;
+
+    pool.paused = false;
+    pool.pause_reason = 0;
+    pool.paused_at = 0;
+
+    emit!(PoolResumedEvent {
+        pool: ctx.accounts.staking_pool.key(),
+        admin: ctx.accounts.admin.key(),
+        paused_duration,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub fn set_reward_rate(
+    ctx: Context<SetRewardRate>,
+    rewards_per_second: u64,
+) -> Result<()> {
+    require_admin_approval(
+        &ctx.accounts.staking_pool,
+        &ctx.accounts.authority.to_account_info(),
+        ctx.remaining_accounts,
+    )?;
+
+    require!(
+        ctx.accounts.staking_pool.admin_timelock_delay == 0,
+        StakingError::TimelockRequired
+    );
+    validate_reward_rate(rewards_per_second)?;
+
+    let vault = &mut ctx.accounts.reward_vault;
+    let clock = Clock::get()?;
+
+    let previous_rewards_per_second = apply_reward_rate(
+        vault,
+        &ctx.accounts.staking_pool,
+        rewards_per_second,
+        ctx.accounts.reward_vault_token.amount,
+        clock.unix_timestamp,
+    )?;
+
+    emit!(RewardVaultUpdatedEvent {
+        pool: ctx.accounts.staking_pool.key(),
+        authority: ctx.accounts.authority.key(),
+        stream: vault.index,
+        previous_rewards_per_second,
+        rewards_per_second,
+        accumulated_rewards_per_share: vault.accumulated_rewards_per_share,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// Starts a new emission period for a stream: deposits `amount` and spreads it,
+// together with whatever the current period has not emitted yet, evenly over
+// the next `duration` seconds. It can only raise the rate of a running stream;
+// rate cuts stay behind `set_reward_rate` and the admin timelock.
+pub fn notify_reward_amount(
+    ctx: Context<NotifyRewardAmount>,
+    amount: u64,
+    duration: i64,
+) -> Result<()> {
+    require_admin_approval(
+        &ctx.accounts.staking_pool,
+        &ctx.accounts.authority.to_account_info(),
+        ctx.remaining_accounts,
+    )?;
+
+    require!(amount > 0, StakingError::InvalidAmount);
+    require!(duration > 0, StakingError::InvalidRewardWindow);
+
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.funder_token_account.to_account_info(),
+                to: ctx.accounts.reward_vault_token.to_account_info(),
+                authority: ctx.accounts.authority.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    ctx.accounts.reward_vault_token.reload()?;
+
+    let pool = &ctx.accounts.staking_pool;
+    let vault = &mut ctx.accounts.reward_vault;
+    let clock = Clock::get()?;
+
+    update_reward_vault(vault, pool, clock.unix_timestamp)?;
+
+    let running = vault.reward_end_time == 0 || clock.unix_timestamp < vault.reward_end_time;
+    let remaining_emissions = calculate_remaining_emissions(vault, pool, clock.unix_timestamp)?;
+    let total_rewards = (amount as u128)
+        .checked_add(remaining_emissions)
+        .ok_or(StakingError::CalculationOverflow)?;
+
+    // Emissions are scaled by the pool multiplier, so the base rate is
+    // scaled back down for the deposit to last exactly `duration`
+    let rewards_per_second = total_rewards
+        .checked_mul(BASIS_POINTS as u128)
+        .ok_or(StakingError::CalculationOverflow)?
+        .checked_div(
+            (duration as u128)
+                .checked_mul(pool.rewards_multiplier as u128)
+                .ok_or(StakingError::CalculationOverflow)?,
+        )
+        .ok_or(StakingError::CalculationOverflow)?;
+    let rewards_per_second = u64::try_from(rewards_per_second)
+        .map_err(|_| StakingError::InvalidRewardRate)?;
+
+    require!(rewards_per_second > 0, StakingError::InvalidRewardRate);
+    require!(
+        !running || rewards_per_second >= vault.rewards_per_second,
+        StakingError::RewardRateDecrease
+    );
+    validate_reward_rate(rewards_per_second)?;
+
+    vault.reward_start_time = clock.unix_timestamp;
+    vault.reward_end_time = clock.unix_timestamp
+        .checked_add(duration)
+        .ok_or(StakingError::CalculationOverflow)?;
+
+    // The carried-over remainder must be funded as well as the new deposit
+    validate_reward_runway(
+        vault,
+        pool,
+        rewards_per_second,
+        ctx.accounts.reward_vault_token.amount,
+        clock.unix_timestamp,
+    )?;
+    vault.rewards_per_second = rewards_per_second;
+
+    emit!(RewardAmountNotifiedEvent {
+        pool: ctx.accounts.staking_pool.key(),
+        authority: ctx.accounts.authority.key(),
+        stream: vault.index,
+        amount,
+        rewards_per_second,
+        reward_start_time: vault.reward_start_time,
+        reward_end_time: vault.reward_end_time,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// Once a stream's campaign has ended, whatever the vault holds beyond the
+// rewards still owed to stakers can be withdrawn by the authority
+pub fn recover_leftover_rewards(ctx: Context<RecoverLeftoverRewards>) -> Result<()> {
+    require_admin_approval(
+        &ctx.accounts.staking_pool,
+        &ctx.accounts.authority.to_account_info(),
+        ctx.remaining_accounts,
+    )?;
+
+    let vault = &mut ctx.accounts.reward_vault;
+    let clock = Clock::get()?;
+
+    require!(
+        vault.reward_end_time > 0 && clock.unix_timestamp >= vault.reward_end_time,
+        StakingError::RewardPeriodActive
+    );
+
+    // Emissions up to the end time count as owed
+    update_reward_vault(vault, &ctx.accounts.staking_pool, clock.unix_timestamp)?;
+
+    let leftover = ctx.accounts.reward_vault_token.amount
+        .saturating_sub(vault.unclaimed_rewards);
+    require!(leftover > 0, StakingError::NoLeftoverRewards);
+
+    transfer_from_reward_vault(
+        &ctx.accounts.token_program,
+        vault,
+        &ctx.accounts.reward_vault_token,
+        ctx.accounts.destination_token_account.to_account_info(),
+        leftover,
+    )?;
+
+    emit!(LeftoverRewardsRecoveredEvent {
+        pool: ctx.accounts.staking_pool.key(),
+        authority: ctx.accounts.authority.key(),
+        stream: vault.index,
+        amount: leftover,
+        destination: ctx.accounts.destination_token_account.key(),
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub fn create_stake_account(
+    ctx: Context<CreateStakeAccount>,
+    delegated_authority: Option<Pubkey>,
+) -> Result<()> {
+    let staker = &mut ctx.accounts.staker_info;
+    staker.owner = ctx.accounts.owner.key();
+    staker.pool = ctx.accounts.staking_pool.key();
+    staker.delegated_authority = delegated_authority;
+    staker.bump = *ctx.bumps.get("staker_info").unwrap();
+
+    emit!(StakeAccountCreatedEvent {
+        pool: ctx.accounts.staking_pool.key(),
+        owner: staker.owner,
+        delegated_authority,
+    });
+
+    Ok(())
+}
+
+// Closes an emptied staker account and returns its rent to the owner.
+// `active_stakers` counts stakers with a non-zero stake, so an empty account
+// stopped counting when its last stake left and the pool is only read here.
+pub fn close_stake_account(ctx: Context<CloseStakeAccount>) -> Result<()> {
+    let staker = &ctx.accounts.staker_info;
+
+    require!(staker.amount == 0, StakingError::StakeAccountNotEmpty);
+    require!(
+        staker.accumulated_rewards.iter().all(|&rewards| rewards == 0),
+        StakingError::StakeAccountNotEmpty
+    );
+    require!(staker.open_positions == 0, StakingError::StakeAccountNotEmpty);
+    require!(staker.unbonding_amount == 0, StakingError::StakeAccountNotEmpty);
+
+    emit!(StakeAccountClosedEvent {
+        pool: ctx.accounts.staking_pool.key(),
+        owner: ctx.accounts.owner.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub fn stake(
+    ctx: Context<Stake>,
+    amount: u64,
+    lock_period: i64,
+) -> Result<()> {
+    require!(amount >= MIN_STAKE_AMOUNT, StakingError::BelowMinimumStake);
+    require!(
+        lock_period >= ctx.accounts.staking_pool.min_stake_duration,
+        StakingError::LockPeriodTooShort
+    );
+    require!(
+        lock_period <= ctx.accounts.staking_pool.max_stake_duration,
+        StakingError::LockPeriodTooLong
+    );
+    require!(!ctx.accounts.staking_pool.paused, StakingError::PoolPaused);
+
+    let staker = &mut ctx.accounts.staker_info;
+    let position = &mut ctx.accounts.stake_position;
+    let clock = Clock::get()?;
+
+    // Update rewards before new stake
+    let streams = load_reward_streams(
+        &ctx.accounts.staking_pool,
+        ctx.remaining_accounts,
+        clock.unix_timestamp,
+    )?;
+    settle_all_pending_rewards(&streams, staker)?;
+
+    // Transfer tokens to stake account
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.user_token_account.to_account_info(),
+                to: ctx.accounts.stake_token_account.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    // Open a new position for this deposit
+    let pool = &mut ctx.accounts.staking_pool;
+    open_position(
+        pool,
+        staker,
+        position,
+        amount,
+        lock_period,
+        clock.unix_timestamp,
+        *ctx.bumps.get("stake_position").unwrap(),
+    )?;
+
+    // Update pool stats
+    sync_stake_weight(pool, &streams, staker)?;
+    persist_reward_streams(&streams)?;
+    pool.total_staked = pool.total_staked
+        .checked_add(amount)
+        .ok_or(StakingError::CalculationOverflow)?;
+    pool.total_deposits = pool.total_deposits
+        .checked_add(1)
+        .ok_or(StakingError::CalculationOverflow)?;
+
+    emit!(StakeEvent {
+        pool: ctx.accounts.staking_pool.key(),
+        user: ctx.accounts.user.key(),
+        position: position.index,
+        amount,
+        timestamp: clock.unix_timestamp,
+        tier: staker.tier,
+        lock_period,
+    });
+
+    Ok(())
+}
+
+pub fn unstake(
+    ctx: Context<Unstake>,
+    amount: u64,
+) -> Result<()> {
+    require!(amount > 0, StakingError::InvalidAmount);
+    require!(
+        ctx.accounts.staking_pool.unbonding_period == 0,
+        StakingError::UnbondingRequired
+    );
+
+    let staker = &mut ctx.accounts.staker_info;
+    let position = &mut ctx.accounts.stake_position;
+    let clock = Clock::get()?;
+
+    require!(
+        clock.unix_timestamp >= position.unlock_time,
+        StakingError::StakeLocked
+    );
+    require!(amount <= position.amount, StakingError::InsufficientStakeBalance);
+
+    // Settle any pending rewards before unstaking
+    let streams = load_reward_streams(
+        &ctx.accounts.staking_pool,
+        ctx.remaining_accounts,
+        clock.unix_timestamp,
+    )?;
+    settle_all_pending_rewards(&streams, staker)?;
+
+    // Transfer unstaked tokens back to user
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.stake_token_account.to_account_info(),
+                to: ctx.accounts.user_token_account.to_account_info(),
+                authority: ctx.accounts.staking_pool.to_account_info(),
+            },
+            &[&[
+                b"staking_pool".as_ref(),
+                ctx.accounts.staking_pool.stake_mint.as_ref(),
+                &ctx.accounts.staking_pool.pool_id.to_le_bytes(),
+                &[ctx.accounts.staking_pool.bump],
+            ]],
+        ),
+        amount,
+    )?;
+
+    // Update position and staker info
+    position.amount = position.amount
+        .checked_sub(amount)
+        .ok_or(StakingError::CalculationOverflow)?;
+    refresh_position_bonus(staker, position)?;
+
+    staker.amount = staker.amount
+        .checked_sub(amount)
+        .ok_or(StakingError::CalculationOverflow)?;
+    staker.tier = calculate_tier(staker.amount);
+    staker.last_reward_time = clock.unix_timestamp;
+
+    // Update pool stats
+    let pool = &mut ctx.accounts.staking_pool;
+    release_active_staker(pool, staker)?;
+    sync_stake_weight(pool, &streams, staker)?;
+    persist_reward_streams(&streams)?;
+    pool.total_staked = pool.total_staked
+        .checked_sub(amount)
+        .ok_or(StakingError::CalculationOverflow)?;
+    pool.total_withdrawals = pool.total_withdrawals
+        .checked_add(1)
+        .ok_or(StakingError::CalculationOverflow)?;
+
+    let position_index = position.index;
+    if position.amount == 0 {
+        close_position(staker, position, ctx.accounts.user.to_account_info())?;
+    }
+
+    emit!(UnstakeEvent {
+        pool: ctx.accounts.staking_pool.key(),
+        user: ctx.accounts.user.key(),
+        position: position_index,
+        amount,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// Exits a position before its unlock time. The penalty decays linearly from
+// `early_unstake_penalty` to zero over the lock and goes to the treasury or,
+// when stake and reward mints match, to the remaining stakers. The net amount
+// is paid out immediately, or queued for unbonding when the pool has a
+// cooldown.
+pub fn early_unstake(
+    ctx: Context<EarlyUnstake>,
+    amount: u64,
+) -> Result<()> {
+    require!(amount > 0, StakingError::InvalidAmount);
+
+    let staker = &mut ctx.accounts.staker_info;
+    let position = &mut ctx.accounts.stake_position;
+    let clock = Clock::get()?;
+
+    require!(
+        clock.unix_timestamp < position.unlock_time,
+        StakingError::StakeNotLocked
+    );
+    require!(amount <= position.amount, StakingError::InsufficientStakeBalance);
+
+    // Settle any pending rewards before unstaking
+    let mut streams = load_reward_streams(
+        &ctx.accounts.staking_pool,
+        ctx.remaining_accounts,
+        clock.unix_timestamp,
+    )?;
+    settle_all_pending_rewards(&streams, staker)?;
+
+    let penalty = calculate_early_unstake_penalty(
+        &ctx.accounts.staking_pool,
+        position,
+        amount,
+        clock.unix_timestamp,
+    )?;
+    let user_amount = amount
+        .checked_sub(penalty)
+        .ok_or(StakingError::CalculationOverflow)?;
+
+    // Update position and staker info
+    position.amount = position.amount
+        .checked_sub(amount)
+        .ok_or(StakingError::CalculationOverflow)?;
+    refresh_position_bonus(staker, position)?;
+
+    staker.amount = staker.amount
+        .checked_sub(amount)
+        .ok_or(StakingError::CalculationOverflow)?;
+    staker.tier = calculate_tier(staker.amount);
+    staker.last_reward_time = clock.unix_timestamp;
+
+    // Update pool stats
+    let pool = &mut ctx.accounts.staking_pool;
+    release_active_staker(pool, staker)?;
+    sync_stake_weight(pool, &streams, staker)?;
+    pool.total_staked = pool.total_staked
+        .checked_sub(amount)
+        .ok_or(StakingError::CalculationOverflow)?;
+
+    // With a cooldown configured, the principal left after the penalty joins
+    // the unbonding queue rather than skipping the wait unlocked stake has
+    let unbonding = pool.unbonding_period > 0;
+    if unbonding {
+        queue_unbonding(pool, staker, user_amount, clock.unix_timestamp)?;
+    } else {
+        pool.total_withdrawals = pool.total_withdrawals
+            .checked_add(1)
+            .ok_or(StakingError::CalculationOverflow)?;
+    }
+
+    pool.total_penalties = pool.total_penalties
+        .checked_add(penalty)
+        .ok_or(StakingError::CalculationOverflow)?;
+
+    // Penalties can only be shared through a stream paying out in the stake
+    // mint. When the pool has one, the exit must pass it rather than steer the
+    // penalty to the treasury with some other token account.
+    let stream = reward_stream_index(&streams, ctx.accounts.reward_vault_token.key());
+    if pool.penalty_destination == PenaltyDestination::Stakers
+        && streams.iter().any(|reward_vault| reward_vault.reward_mint == pool.stake_mint)
+    {
+        require!(
+            matches!(stream, Some(stream) if streams[stream].reward_mint == pool.stake_mint),
+            StakingError::InvalidTokenAccount
+        );
+    }
+
+    let redistributed = penalty > 0
+        && pool.penalty_destination == PenaltyDestination::Stakers
+        && pool.total_weighted_stake > 0
+        && stream.is_some()
+        && ctx.accounts.stake_token_account.mint == ctx.accounts.reward_vault_token.mint;
+
+    if let Some(stream) = stream.filter(|_| redistributed) {
+        // Share the penalty with everyone still staked
+        let reward_vault = &mut streams[stream];
+        let rewards_per_share = (penalty as u128)
+            .checked_mul(REWARDS_PRECISION)
+            .ok_or(StakingError::CalculationOverflow)?
+            .checked_div(pool.total_weighted_stake as u128)
+            .ok_or(StakingError::CalculationOverflow)?;
+        reward_vault.accumulated_rewards_per_share = reward_vault.accumulated_rewards_per_share
+            .checked_add(rewards_per_share)
+            .ok_or(StakingError::CalculationOverflow)?;
+        reward_vault.unclaimed_rewards = reward_vault.unclaimed_rewards
+            .checked_add(penalty)
+            .ok_or(StakingError::CalculationOverflow)?;
+    }
+    persist_reward_streams(&streams)?;
+
+    let pool_id_bytes = pool.pool_id.to_le_bytes();
+    let pool_seeds: &[&[u8]] = &[
+        b"staking_pool".as_ref(),
+        pool.stake_mint.as_ref(),
+        &pool_id_bytes,
+        &[pool.bump],
+    ];
+
+    // Transfer unstaked tokens, net of penalty, back to user
+    if !unbonding {
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.stake_token_account.to_account_info(),
+                    to: ctx.accounts.user_token_account.to_account_info(),
+                    authority: pool.to_account_info(),
+                },
+                &[pool_seeds],
+            ),
+            user_amount,
+        )?;
+    }
+
+    // Transfer penalty
+    if penalty > 0 {
+        let penalty_account = if redistributed {
+            ctx.accounts.reward_vault_token.to_account_info()
+        } else {
+            ctx.accounts.treasury_token_account.to_account_info()
+        };
+
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.stake_token_account.to_account_info(),
+                    to: penalty_account,
+                    authority: pool.to_account_info(),
+                },
+                &[pool_seeds],
+            ),
+            penalty,
+        )?;
+    }
+
+    let position_index = position.index;
+    if position.amount == 0 {
+        close_position(staker, position, ctx.accounts.user.to_account_info())?;
+    }
+
+    emit!(EarlyUnstakeEvent {
+        pool: ctx.accounts.staking_pool.key(),
+        user: ctx.accounts.user.key(),
+        position: position_index,
+        amount: user_amount,
+        penalty,
+        redistributed,
+        release_time: if unbonding { staker.unbonding_release_time } else { 0 },
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// With a cooldown configured, exiting is two-phase: `request_unstake` moves
+// unlocked stake into the staker's unbonding balance, which earns nothing,
+// and `withdraw_unbonded` releases it once the cooldown has passed.
+pub fn request_unstake(
+    ctx: Context<RequestUnstake>,
+    amount: u64,
+) -> Result<()> {
+    require!(amount > 0, StakingError::InvalidAmount);
+
+    let staker = &mut ctx.accounts.staker_info;
+    let position = &mut ctx.accounts.stake_position;
+    let clock = Clock::get()?;
+
+    require!(
+        clock.unix_timestamp >= position.unlock_time,
+        StakingError::StakeLocked
+    );
+    require!(amount <= position.amount, StakingError::InsufficientStakeBalance);
+
+    // Settle rewards up to the moment the amount stops earning
+    let streams = load_reward_streams(
+        &ctx.accounts.staking_pool,
+        ctx.remaining_accounts,
+        clock.unix_timestamp,
+    )?;
+    settle_all_pending_rewards(&streams, staker)?;
+
+    position.amount = position.amount
+        .checked_sub(amount)
+        .ok_or(StakingError::CalculationOverflow)?;
+    refresh_position_bonus(staker, position)?;
+
+    let pool = &mut ctx.accounts.staking_pool;
+    staker.amount = staker.amount
+        .checked_sub(amount)
+        .ok_or(StakingError::CalculationOverflow)?;
+    staker.tier = calculate_tier(staker.amount);
+    staker.last_reward_time = clock.unix_timestamp;
+    release_active_staker(pool, staker)?;
+    queue_unbonding(pool, staker, amount, clock.unix_timestamp)?;
+
+    // Update pool stats
+    sync_stake_weight(pool, &streams, staker)?;
+    persist_reward_streams(&streams)?;
+    pool.total_staked = pool.total_staked
+        .checked_sub(amount)
+        .ok_or(StakingError::CalculationOverflow)?;
+
+    let position_index = position.index;
+    if position.amount == 0 {
+        close_position(staker, position, ctx.accounts.user.to_account_info())?;
+    }
+
+    emit!(UnstakeRequestedEvent {
+        pool: ctx.accounts.staking_pool.key(),
+        user: ctx.accounts.user.key(),
+        position: position_index,
+        amount,
+        unbonding_amount: staker.unbonding_amount,
+        release_time: staker.unbonding_release_time,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub fn withdraw_unbonded(ctx: Context<WithdrawUnbonded>) -> Result<()> {
+    let staker = &mut ctx.accounts.staker_info;
+    let amount = staker.unbonding_amount;
+    let clock = Clock::get()?;
+
+    require!(amount > 0, StakingError::NoUnbondingStake);
+
+    // Emergency mode releases principal straight away, cooldown included
+    require!(
+        ctx.accounts.staking_pool.emergency_mode
+            || clock.unix_timestamp >= staker.unbonding_release_time,
+        StakingError::UnbondingNotComplete
+    );
+
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.stake_token_account.to_account_info(),
+                to: ctx.accounts.user_token_account.to_account_info(),
+                authority: ctx.accounts.staking_pool.to_account_info(),
+            },
+            &[&[
+                b"staking_pool".as_ref(),
+                ctx.accounts.staking_pool.stake_mint.as_ref(),
+                &ctx.accounts.staking_pool.pool_id.to_le_bytes(),
+                &[ctx.accounts.staking_pool.bump],
+            ]],
+        ),
+        amount,
+    )?;
+
+    staker.unbonding_amount = 0;
+    staker.unbonding_release_time = 0;
+
+    let pool = &mut ctx.accounts.staking_pool;
+    pool.total_unbonding = pool.total_unbonding
+        .checked_sub(amount)
+        .ok_or(StakingError::CalculationOverflow)?;
+    pool.total_withdrawals = pool.total_withdrawals
+        .checked_add(1)
+        .ok_or(StakingError::CalculationOverflow)?;
+
+    emit!(UnbondingWithdrawnEvent {
+        pool: ctx.accounts.staking_pool.key(),
+        user: ctx.accounts.user.key(),
+        amount,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// Re-stakes the whole unbonding balance into a new position
+pub fn cancel_unbonding(
+    ctx: Context<CancelUnbonding>,
+    lock_period: i64,
+) -> Result<()> {
+    require!(!ctx.accounts.staking_pool.paused, StakingError::PoolPaused);
+    require!(
+        lock_period >= ctx.accounts.staking_pool.min_stake_duration,
+        StakingError::LockPeriodTooShort
+    );
+    require!(
+        lock_period <= ctx.accounts.staking_pool.max_stake_duration,
+        StakingError::LockPeriodTooLong
+    );
+
+    let staker = &mut ctx.accounts.staker_info;
+    let position = &mut ctx.accounts.stake_position;
+    let amount = staker.unbonding_amount;
+    let clock = Clock::get()?;
+
+    require!(amount > 0, StakingError::NoUnbondingStake);
+    require!(amount >= MIN_STAKE_AMOUNT, StakingError::BelowMinimumStake);
+
+    let streams = load_reward_streams(
+        &ctx.accounts.staking_pool,
+        ctx.remaining_accounts,
+        clock.unix_timestamp,
+    )?;
+    settle_all_pending_rewards(&streams, staker)?;
+
+    staker.unbonding_amount = 0;
+    staker.unbonding_release_time = 0;
+
+    let pool = &mut ctx.accounts.staking_pool;
+    open_position(
+        pool,
+        staker,
+        position,
+        amount,
+        lock_period,
+        clock.unix_timestamp,
+        *ctx.bumps.get("stake_position").unwrap(),
+    )?;
+
+    // Update pool stats
+    sync_stake_weight(pool, &streams, staker)?;
+    persist_reward_streams(&streams)?;
+    pool.total_staked = pool.total_staked
+        .checked_add(amount)
+        .ok_or(StakingError::CalculationOverflow)?;
+    pool.total_unbonding = pool.total_unbonding
+        .checked_sub(amount)
+        .ok_or(StakingError::CalculationOverflow)?;
+
+    emit!(UnbondingCancelledEvent {
+        pool: ctx.accounts.staking_pool.key(),
+        user: ctx.accounts.user.key(),
+        position: position.index,
+        amount,
+        lock_period,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// Restarts the position's lock at `new_lock_period` from now. The new unlock
+// time must land after the current one, so a lock can only be lengthened.
+pub fn extend_lock(
+    ctx: Context<ExtendLock>,
+    new_lock_period: i64,
+) -> Result<()> {
+    require!(!ctx.accounts.staking_pool.paused, StakingError::PoolPaused);
+    require!(
+        new_lock_period >= ctx.accounts.staking_pool.min_stake_duration,
+        StakingError::LockPeriodTooShort
+    );
+    require!(
+        new_lock_period <= ctx.accounts.staking_pool.max_stake_duration,
+        StakingError::LockPeriodTooLong
+    );
+
+    let staker = &mut ctx.accounts.staker_info;
+    let position = &mut ctx.accounts.stake_position;
+    let clock = Clock::get()?;
+
+    let unlock_time = clock.unix_timestamp
+        .checked_add(new_lock_period)
+        .ok_or(StakingError::CalculationOverflow)?;
+    require!(
+        unlock_time > position.unlock_time,
+        StakingError::LockExtensionTooShort
+    );
+
+    // Settle rewards earned under the current bonus
+    let streams = load_reward_streams(
+        &ctx.accounts.staking_pool,
+        ctx.remaining_accounts,
+        clock.unix_timestamp,
+    )?;
+    settle_all_pending_rewards(&streams, staker)?;
+
+    let previous_unlock_time = position.unlock_time;
+    position.lock_period = new_lock_period;
+    position.start_time = clock.unix_timestamp;
+    position.unlock_time = unlock_time;
+    refresh_position_bonus(staker, position)?;
+
+    let pool = &mut ctx.accounts.staking_pool;
+    sync_stake_weight(pool, &streams, staker)?;
+    persist_reward_streams(&streams)?;
+
+    emit!(LockExtendedEvent {
+        pool: ctx.accounts.staking_pool.key(),
+        user: ctx.accounts.user.key(),
+        position: position.index,
+        previous_unlock_time,
+        unlock_time,
+        lock_period: new_lock_period,
+        lock_bonus: calculate_lock_bonus(new_lock_period)?,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// Claims a single reward stream; `claim_all_rewards` sweeps every stream
+pub fn claim_rewards(ctx: Context<ClaimRewards>) -> Result<()> {
+    require!(!ctx.accounts.staking_pool.paused, StakingError::PoolPaused);
+
+    let staker = &mut ctx.accounts.staker_info;
+    let reward_vault = &mut ctx.accounts.reward_vault;
+    let stream = reward_vault.index as usize;
+    let clock = Clock::get()?;
+
+    update_reward_vault(
+        reward_vault,
+        &ctx.accounts.staking_pool,
+        clock.unix_timestamp,
+    )?;
+    settle_pending_rewards(reward_vault, staker)?;
+
+    let owed = staker.accumulated_rewards[stream];
+
+    require!(owed > 0, StakingError::NoRewardsToClaim);
+
+    let total_rewards = cap_reward_payout(
+        reward_vault,
+        ctx.accounts.reward_vault_token.amount,
+        ctx.accounts.user.key(),
+        owed,
+        clock.unix_timestamp,
+    );
+    require!(total_rewards > 0, StakingError::RewardVaultDepleted);
+
+    let treasury_fee = calculate_fee(total_rewards, ctx.accounts.staking_pool.treasury_fee)?;
+    let user_reward = total_rewards
+        .checked_sub(treasury_fee)
+        .ok_or(StakingError::CalculationOverflow)?;
+
+    // Transfer rewards
+    transfer_from_reward_vault(
+        &ctx.accounts.token_program,
+        reward_vault,
+        &ctx.accounts.reward_vault_token,
+        ctx.accounts.user_token_account.to_account_info(),
+        user_reward,
+    )?;
+
+    // Transfer treasury fee
+    transfer_from_reward_vault(
+        &ctx.accounts.token_program,
+        reward_vault,
+        &ctx.accounts.reward_vault_token,
+        ctx.accounts.treasury_account.to_account_info(),
+        treasury_fee,
+    )?;
+
+    release_unclaimed_rewards(reward_vault, total_rewards);
+    mark_rewards_paid(staker, stream, total_rewards, clock.unix_timestamp)?;
+    record_reward_payout(&mut ctx.accounts.staking_pool, total_rewards, treasury_fee)?;
+
+    emit!(RewardsClaimedEvent {
+        pool: ctx.accounts.staking_pool.key(),
+        user: ctx.accounts.user.key(),
+        stream: reward_vault.index,
+        amount: user_reward,
+        treasury_fee,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// Claims every reward stream of the pool. Each stream is passed as a group of
+// remaining accounts, in index order: the reward vault, its token account,
+// the user's token account and the treasury's token account for that mint.
+pub fn claim_all_rewards<'info>(
+    ctx: Context<'_, '_, '_, 'info, ClaimAllRewards<'info>>,
+) -> Result<()> {
+    require!(!ctx.accounts.staking_pool.paused, StakingError::PoolPaused);
+
+    let stream_count = ctx.accounts.staking_pool.reward_stream_count as usize;
+    require!(
+        ctx.remaining_accounts.len() == stream_count * CLAIM_ACCOUNTS_PER_STREAM,
+        StakingError::InvalidRewardStream
+    );
+
+    let staker = &mut ctx.accounts.staker_info;
+    let pool_key = ctx.accounts.staking_pool.key();
+    let treasury = ctx.accounts.staking_pool.treasury;
+    let clock = Clock::get()?;
+    let mut claimed = false;
+
+    for (stream, accounts) in ctx.remaining_accounts.chunks(CLAIM_ACCOUNTS_PER_STREAM).enumerate() {
+        let mut reward_vault = load_reward_stream(pool_key, &accounts[0], stream)?;
+        let reward_vault_token = Account::<TokenAccount>::try_from(&accounts[1])?;
+        let user_token_account = Account::<TokenAccount>::try_from(&accounts[2])?;
+        let treasury_account = Account::<TokenAccount>::try_from(&accounts[3])?;
+
+        require_keys_eq!(
+            reward_vault_token.key(),
+            reward_vault.token_account,
+            StakingError::InvalidTokenAccount
+        );
+        require_keys_eq!(
+            user_token_account.owner,
+            ctx.accounts.user.key(),
+            StakingError::UnauthorizedAccess
+        );
+        require_keys_eq!(
+            user_token_account.mint,
+            reward_vault.reward_mint,
+            StakingError::MintMismatch
+        );
+        require_keys_eq!(treasury_account.owner, treasury, StakingError::InvalidTokenAccount);
+        require_keys_eq!(
+            treasury_account.mint,
+            reward_vault.reward_mint,
+            StakingError::MintMismatch
+        );
+
+        update_reward_vault(
+            &mut reward_vault,
+            &ctx.accounts.staking_pool,
+            clock.unix_timestamp,
+        )?;
+        settle_pending_rewards(&reward_vault, staker)?;
+
+        // Streams with nothing owed, or nothing left to pay, are skipped
+        let total_rewards = cap_reward_payout(
+            &reward_vault,
+            reward_vault_token.amount,
+            ctx.accounts.user.key(),
+            staker.accumulated_rewards[stream],
+            clock.unix_timestamp,
+        );
+        if total_rewards > 0 {
+            let treasury_fee = calculate_fee(total_rewards, ctx.accounts.staking_pool.treasury_fee)?;
+            let user_reward = total_rewards
+                .checked_sub(treasury_fee)
+                .ok_or(StakingError::CalculationOverflow)?;
+
+            transfer_from_reward_vault(
+                &ctx.accounts.token_program,
+                &reward_vault,
+                &reward_vault_token,
+                user_token_account.to_account_info(),
+                user_reward,
+            )?;
+            transfer_from_reward_vault(
+                &ctx.accounts.token_program,
+                &reward_vault,
+                &reward_vault_token,
+                treasury_account.to_account_info(),
+                treasury_fee,
+            )?;
+
+            release_unclaimed_rewards(&mut reward_vault, total_rewards);
+            mark_rewards_paid(staker, stream, total_rewards, clock.unix_timestamp)?;
+            record_reward_payout(&mut ctx.accounts.staking_pool, total_rewards, treasury_fee)?;
+            claimed = true;
+
+            emit!(RewardsClaimedEvent {
+                pool: pool_key,
+                user: ctx.accounts.user.key(),
+                stream: reward_vault.index,
+                amount: user_reward,
+                treasury_fee,
+                timestamp: clock.unix_timestamp,
+            });
+        }
+
+        reward_vault.exit(&crate::ID)?;
+    }
+
+    require!(claimed, StakingError::NoRewardsToClaim);
+
+    Ok(())
+}
+
+// Restakes the staker's rewards from the stream paying out in the stake mint,
+// net of the treasury fee, into one of its positions. The position keeps its
+// lock; only its principal grows.
+pub fn compound_rewards<'info>(
+    ctx: Context<'_, '_, '_, 'info, CompoundRewards<'info>>,
+) -> Result<()> {
+    require!(!ctx.accounts.staking_pool.paused, StakingError::PoolPaused);
+    require!(
+        ctx.accounts.stake_token_account.mint == ctx.accounts.reward_vault_token.mint,
+        StakingError::MintMismatch
+    );
+
+    let staker = &mut ctx.accounts.staker_info;
+    let position = &mut ctx.accounts.stake_position;
+    let clock = Clock::get()?;
+
+    let mut streams = load_reward_streams(
+        &ctx.accounts.staking_pool,
+        ctx.remaining_accounts,
+        clock.unix_timestamp,
+    )?;
+    settle_all_pending_rewards(&streams, staker)?;
+
+    let stream = reward_stream_index(&streams, ctx.accounts.reward_vault_token.key())
+        .ok_or(StakingError::InvalidTokenAccount)?;
+    let owed = staker.accumulated_rewards[stream];
+
+    require!(owed > 0, StakingError::NoRewardsToClaim);
+
+    let total_rewards = cap_reward_payout(
+        &streams[stream],
+        ctx.accounts.reward_vault_token.amount,
+        ctx.accounts.user.key(),
+        owed,
+        clock.unix_timestamp,
+    );
+    require!(total_rewards > 0, StakingError::RewardVaultDepleted);
+
+    let treasury_fee = calculate_fee(total_rewards, ctx.accounts.staking_pool.treasury_fee)?;
+    let compound_amount = total_rewards
+        .checked_sub(treasury_fee)
+        .ok_or(StakingError::CalculationOverflow)?;
+
+    // Move rewards into the stake account as principal
+    transfer_from_reward_vault(
+        &ctx.accounts.token_program,
+        &streams[stream],
+        &ctx.accounts.reward_vault_token,
+        ctx.accounts.stake_token_account.to_account_info(),
+        compound_amount,
+    )?;
+
+    // Transfer treasury fee
+    transfer_from_reward_vault(
+        &ctx.accounts.token_program,
+        &streams[stream],
+        &ctx.accounts.reward_vault_token,
+        ctx.accounts.treasury_account.to_account_info(),
+        treasury_fee,
+    )?;
+
+    release_unclaimed_rewards(&mut streams[stream], total_rewards);
+
+    let pool = &mut ctx.accounts.staking_pool;
+    mark_rewards_paid(staker, stream, total_rewards, clock.unix_timestamp)?;
+    credit_compound(
+        pool,
+        &streams,
+        staker,
+        position,
+        compound_amount,
+        clock.unix_timestamp,
+    )?;
+    persist_reward_streams(&streams)?;
+    record_reward_payout(pool, total_rewards, treasury_fee)?;
+
+    emit!(RewardsCompoundedEvent {
+        pool: ctx.accounts.staking_pool.key(),
+        user: ctx.accounts.user.key(),
+        stream: stream as u8,
+        position: position.index,
+        amount: compound_amount,
+        treasury_fee,
+        tier: staker.tier,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub fn set_auto_compound(
+    ctx: Context<SetAutoCompound>,
+    enabled: bool,
+) -> Result<()> {
+    let staker = &mut ctx.accounts.staker_info;
+    staker.auto_compound = enabled;
+    staker.auto_compound_position = ctx.accounts.stake_position.index;
+
+    emit!(AutoCompoundUpdatedEvent {
+        pool: ctx.accounts.staking_pool.key(),
+        user: ctx.accounts.user.key(),
+        enabled,
+        position: staker.auto_compound_position,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// Permissionless compounding for opted-in stakers. The keeper is paid
+// `keeper_fee` out of the compounded rewards.
+pub fn crank_compound<'info>(
+    ctx: Context<'_, '_, '_, 'info, CrankCompound<'info>>,
+) -> Result<()> {
+    require!(!ctx.accounts.staking_pool.paused, StakingError::PoolPaused);
+    require!(
+        ctx.accounts.stake_token_account.mint == ctx.accounts.reward_vault_token.mint,
+        StakingError::MintMismatch
+    );
+
+    let staker = &mut ctx.accounts.staker_info;
+    let position = &mut ctx.accounts.stake_position;
+    let clock = Clock::get()?;
+
+    require!(staker.auto_compound, StakingError::AutoCompoundDisabled);
+    require!(
+        clock.unix_timestamp
+            >= staker.last_compound_time.saturating_add(ctx.accounts.staking_pool.min_compound_interval),
+        StakingError::CompoundTooSoon
+    );
+
+    let mut streams = load_reward_streams(
+        &ctx.accounts.staking_pool,
+        ctx.remaining_accounts,
+        clock.unix_timestamp,
+    )?;
+    settle_all_pending_rewards(&streams, staker)?;
+
+    let stream = reward_stream_index(&streams, ctx.accounts.reward_vault_token.key())
+        .ok_or(StakingError::InvalidTokenAccount)?;
+    let owed = staker.accumulated_rewards[stream];
+
+    require!(owed > 0, StakingError::NoRewardsToClaim);
+
+    let total_rewards = cap_reward_payout(
+        &streams[stream],
+        ctx.accounts.reward_vault_token.amount,
+        staker.owner,
+        owed,
+        clock.unix_timestamp,
+    );
+    require!(total_rewards > 0, StakingError::RewardVaultDepleted);
+
+    let treasury_fee = calculate_fee(total_rewards, ctx.accounts.staking_pool.treasury_fee)?;
+    let keeper_bounty = calculate_fee(total_rewards, ctx.accounts.staking_pool.keeper_fee)?;
+    let compound_amount = total_rewards
+        .checked_sub(treasury_fee)
+        .ok_or(StakingError::CalculationOverflow)?
+        .checked_sub(keeper_bounty)
+        .ok_or(StakingError::CalculationOverflow)?;
+
+    // Move rewards into the stake account as principal
+    transfer_from_reward_vault(
+        &ctx.accounts.token_program,
+        &streams[stream],
+        &ctx.accounts.reward_vault_token,
+        ctx.accounts.stake_token_account.to_account_info(),
+        compound_amount,
+    )?;
+
+    // Transfer treasury fee
+    transfer_from_reward_vault(
+        &ctx.accounts.token_program,
+        &streams[stream],
+        &ctx.accounts.reward_vault_token,
+        ctx.accounts.treasury_account.to_account_info(),
+        treasury_fee,
+    )?;
+
+    // Pay the keeper
+    transfer_from_reward_vault(
+        &ctx.accounts.token_program,
+        &streams[stream],
+        &ctx.accounts.reward_vault_token,
+        ctx.accounts.keeper_token_account.to_account_info(),
+        keeper_bounty,
+    )?;
+
+    release_unclaimed_rewards(&mut streams[stream], total_rewards);
+
+    let pool = &mut ctx.accounts.staking_pool;
+    mark_rewards_paid(staker, stream, total_rewards, clock.unix_timestamp)?;
+    credit_compound(
+        pool,
+        &streams,
+        staker,
+        position,
+        compound_amount,
+        clock.unix_timestamp,
+    )?;
+    persist_reward_streams(&streams)?;
+    record_reward_payout(pool, total_rewards, treasury_fee)?;
+
+    emit!(RewardsCompoundedEvent {
+        pool: ctx.accounts.staking_pool.key(),
+        user: staker.owner,
+        stream: stream as u8,
+        position: position.index,
+        amount: compound_amount,
+        treasury_fee,
+        tier: staker.tier,
+        timestamp: clock.unix_timestamp,
+    });
+
+    emit!(CompoundCrankedEvent {
+        pool: ctx.accounts.staking_pool.key(),
+        keeper: ctx.accounts.keeper.key(),
+        user: staker.owner,
+        keeper_bounty,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// Admin-driven exit that ignores locks and forfeits the withdrawn amount's
+// share of unclaimed rewards. The position is closed once it is fully
+// withdrawn.
+pub fn emergency_unstake(ctx: Context<EmergencyUnstake>, amount: u64) -> Result<()> {
+    let staker = &mut ctx.accounts.staker_info;
+    let position = &mut ctx.accounts.stake_position;
+    let clock = Clock::get()?;
+
+    require!(amount > 0, StakingError::InvalidAmount);
+    require!(amount <= position.amount, StakingError::InsufficientStakeBalance);
+
+    // Bring the accumulators up to date before the stake weight is removed
+    let mut streams = load_reward_streams(
+        &ctx.accounts.staking_pool,
+        ctx.remaining_accounts,
+        clock.unix_timestamp,
+    )?;
+
+    transfer_from_stake_vault(
+        &ctx.accounts.token_program,
+        &ctx.accounts.staking_pool,
+        &ctx.accounts.stake_token_account,
+        ctx.accounts.user_token_account.to_account_info(),
+        amount,
+    )?;
+
+    let pool = &mut ctx.accounts.staking_pool;
+    debit_forfeited_stake(pool, &mut streams, staker, position, amount, clock.unix_timestamp)?;
+    persist_reward_streams(&streams)?;
+    pool.total_withdrawals = pool.total_withdrawals
+        .checked_add(1)
+        .ok_or(StakingError::CalculationOverflow)?;
+
+    let position_index = position.index;
+    let remaining = position.amount;
+    if remaining == 0 {
+        close_position(staker, position, ctx.accounts.user.to_account_info())?;
+    }
+
+    emit!(EmergencyUnstakeEvent {
+        pool: ctx.accounts.staking_pool.key(),
+        user: ctx.accounts.user.key(),
+        position: position_index,
+        amount,
+        remaining,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub fn set_emergency_mode(
+    ctx: Context<SetEmergencyMode>,
+    enabled: bool,
+) -> Result<()> {
+    let pool = &mut ctx.accounts.staking_pool;
+
+    // Emergency exits are only opened on a pool that no longer takes deposits
+    if enabled {
+        require!(pool.paused, StakingError::PoolNotPaused);
+    }
+
+    pool.emergency_mode = enabled;
+
+    emit!(EmergencyModeUpdatedEvent {
+        pool: ctx.accounts.staking_pool.key(),
+        emergency_admin: ctx.accounts.emergency_admin.key(),
+        enabled,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// Self-serve exit while the pool is in emergency mode. Returns the whole
+// position regardless of its lock and forfeits its share of unclaimed
+// rewards.
+pub fn emergency_withdraw(ctx: Context<EmergencyWithdraw>) -> Result<()> {
+    require!(
+        ctx.accounts.staking_pool.emergency_mode,
+        StakingError::EmergencyModeNotActive
+    );
+
+    let staker = &mut ctx.accounts.staker_info;
+    let position = &mut ctx.accounts.stake_position;
+    let amount = position.amount;
+    let clock = Clock::get()?;
+
+    require!(amount > 0, StakingError::NoStakeToUnstake);
+
+    // Bring the accumulators up to date before the stake weight is removed
+    let mut streams = load_reward_streams(
+        &ctx.accounts.staking_pool,
+        ctx.remaining_accounts,
+        clock.unix_timestamp,
+    )?;
+
+    transfer_from_stake_vault(
+        &ctx.accounts.token_program,
+        &ctx.accounts.staking_pool,
+        &ctx.accounts.stake_token_account,
+        ctx.accounts.user_token_account.to_account_info(),
+        amount,
+    )?;
+
+    let pool = &mut ctx.accounts.staking_pool;
+    debit_forfeited_stake(pool, &mut streams, staker, position, amount, clock.unix_timestamp)?;
+    persist_reward_streams(&streams)?;
+    pool.total_withdrawals = pool.total_withdrawals
+        .checked_add(1)
+        .ok_or(StakingError::CalculationOverflow)?;
+
+    let position_index = position.index;
+    close_position(staker, position, ctx.accounts.user.to_account_info())?;
+
+    emit!(EmergencyWithdrawEvent {
+        pool: ctx.accounts.staking_pool.key(),
+        user: ctx.accounts.user.key(),
+        position: position_index,
+        amount,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub fn get_pool_stats(ctx: Context<GetPoolStats>) -> Result<PoolStats> {
+    let pool = &ctx.accounts.staking_pool;
+    let reward_vault = &ctx.accounts.reward_vault;
+
+    Ok(PoolStats {
+        total_staked: pool.total_staked,
+        total_weighted_stake: pool.total_weighted_stake,
+        total_unbonding: pool.total_unbonding,
+        total_rewards_distributed: pool.total_rewards_distributed,
+        total_treasury_fees: pool.total_treasury_fees,
+        total_penalties: pool.total_penalties,
+        active_stakers: pool.active_stakers,
+        total_deposits: pool.total_deposits,
+        total_withdrawals: pool.total_withdrawals,
+        reward_stream_count: pool.reward_stream_count,
+        rewards_per_second: reward_vault.rewards_per_second,
+        reward_vault_balance: ctx.accounts.reward_vault_token.amount,
+        unclaimed_rewards: reward_vault.unclaimed_rewards,
+    })
+}
+
+// Helper functions
+fn update_reward_vault(
+    reward_vault: &mut RewardVault,
+    pool: &StakingPool,
+    current_time: i64,
+) -> Result<()> {
+    if current_time <= reward_vault.last_update_time {
+        return Ok(());
+    }
+
+    // Emissions only accrue inside the stream's start and end times
+    let accrual_start = reward_vault.last_update_time.max(reward_vault.reward_start_time);
+    let accrual_end = if reward_vault.reward_end_time > 0 {
+        current_time.min(reward_vault.reward_end_time)
+    } else {
+        current_time
+    };
+
+    if pool.total_weighted_stake > 0 && accrual_end > accrual_start {
+        // Emissions are scaled by the pool-wide rewards multiplier
+        let time_delta = (accrual_end - accrual_start) as u128;
+        let emitted = calculate_emissions(reward_vault.rewards_per_second, pool, time_delta)?;
+        let rewards_per_share = (reward_vault.rewards_per_second as u128)
+            .checked_mul(time_delta)
+            .ok_or(StakingError::CalculationOverflow)?
+            .checked_mul(pool.rewards_multiplier as u128)
+            .ok_or(StakingError::CalculationOverflow)?
+            .checked_mul(REWARDS_PRECISION)
+            .ok_or(StakingError::CalculationOverflow)?
+            .checked_div(
+                (pool.total_weighted_stake as u128)
+                    .checked_mul(BASIS_POINTS as u128)
+                    .ok_or(StakingError::CalculationOverflow)?,
+            )
+            .ok_or(StakingError::CalculationOverflow)?;
+
+        reward_vault.accumulated_rewards_per_share = reward_vault.accumulated_rewards_per_share
+            .checked_add(rewards_per_share)
+            .ok_or(StakingError::CalculationOverflow)?;
+        reward_vault.unclaimed_rewards = reward_vault.unclaimed_rewards
+            .checked_add(emitted)
+            .ok_or(StakingError::CalculationOverflow)?;
+    }
+
+    reward_vault.last_update_time = current_time;
+    Ok(())
+}
+
+// Tokens a stream emits over `time_delta` seconds at the given rate
+fn calculate_emissions(
+    rewards_per_second: u64,
+    pool: &StakingPool,
+    time_delta: u128,
+) -> Result<u64> {
+    let emitted = (rewards_per_second as u128)
+        .checked_mul(time_delta)
+        .ok_or(StakingError::CalculationOverflow)?
+        .checked_mul(pool.rewards_multiplier as u128)
+        .ok_or(StakingError::CalculationOverflow)?
+        .checked_div(BASIS_POINTS as u128)
+        .ok_or(StakingError::CalculationOverflow)?;
+
+    u64::try_from(emitted).map_err(|_| StakingError::CalculationOverflow.into())
+}
+
+// What the stream would still emit before its end time. Open-ended streams
+// have no scheduled remainder.
+fn calculate_remaining_emissions(
+    reward_vault: &RewardVault,
+    pool: &StakingPool,
+    current_time: i64,
+) -> Result<u128> {
+    if reward_vault.reward_end_time == 0 || current_time >= reward_vault.reward_end_time {
+        return Ok(0);
+    }
+
+    let remaining = reward_vault.reward_end_time - current_time.max(reward_vault.reward_start_time);
+    Ok(calculate_emissions(reward_vault.rewards_per_second, pool, remaining as u128)? as u128)
+}
+
+// Tokens the vault holds beyond what it already owes stakers must cover the
+// rate until the campaign ends, or for MIN_REWARD_RUNWAY on an open-ended
+// stream
+fn validate_reward_runway(
+    reward_vault: &RewardVault,
+    pool: &StakingPool,
+    rewards_per_second: u64,
+    vault_balance: u64,
+    current_time: i64,
+) -> Result<()> {
+    let runway = if reward_vault.reward_end_time > 0 {
+        reward_vault.reward_end_time
+            .saturating_sub(current_time.max(reward_vault.reward_start_time))
+            .max(0)
+    } else {
+        MIN_REWARD_RUNWAY
+    };
+
+    let required = calculate_emissions(rewards_per_second, pool, runway as u128)?;
+    let available = vault_balance.saturating_sub(reward_vault.unclaimed_rewards);
+    require!(required <= available, StakingError::InsufficientRewardFunding);
+    Ok(())
+}
+
+// Caps a payout at what the reward vault holds. The shortfall stays in the
+// staker's accumulated rewards for a later claim.
+fn cap_reward_payout(
+    reward_vault: &RewardVault,
+    vault_balance: u64,
+    user: Pubkey,
+    owed: u64,
+    current_time: i64,
+) -> u64 {
+    if owed <= vault_balance {
+        return owed;
+    }
+
+    emit!(RewardVaultDepletedEvent {
+        pool: reward_vault.pool,
+        user,
+        stream: reward_vault.index,
+        owed,
+        paid: vault_balance,
+        vault_balance,
+        timestamp: current_time,
+    });
+
+    vault_balance
+}
+
+// Rewards leaving the stream, paid out or forfeited, are no longer owed.
+// Per-share rounding can leave stakers a few units over the tracked total.
+fn release_unclaimed_rewards(reward_vault: &mut RewardVault, amount: u64) {
+    reward_vault.unclaimed_rewards = reward_vault.unclaimed_rewards.saturating_sub(amount);
+}
+
+// Settles emissions at the old rate before switching to the new one, so a
+// rate change never alters rewards that were already earned
+fn apply_reward_rate(
+    reward_vault: &mut RewardVault,
+    pool: &StakingPool,
+    rewards_per_second: u64,
+    vault_balance: u64,
+    current_time: i64,
+) -> Result<u64> {
+    update_reward_vault(reward_vault, pool, current_time)?;
+
+    if rewards_per_second > reward_vault.rewards_per_second {
+        validate_reward_runway(
+            reward_vault,
+            pool,
+            rewards_per_second,
+            vault_balance,
+            current_time,
+        )?;
+    }
+
+    let previous_rewards_per_second = reward_vault.rewards_per_second;
+    reward_vault.rewards_per_second = rewards_per_second;
+
+    Ok(previous_rewards_per_second)
+}
+
+// Rewards earned since the staker's debt was last reset. The caller is
+// expected to have brought `reward_vault` up to date first.
+fn calculate_pending_rewards(
+    reward_vault: &RewardVault,
+    staker: &StakerInfo,
+) -> Result<u64> {
+    let accrued = calculate_reward_debt(reward_vault, staker.weighted_amount)?;
+    let pending = accrued
+        .checked_sub(staker.reward_debts[reward_vault.index as usize])
+        .ok_or(StakingError::CalculationOverflow)?;
+
+    u64::try_from(pending).map_err(|_| StakingError::CalculationOverflow.into())
+}
+
+fn settle_pending_rewards(
+    reward_vault: &RewardVault,
+    staker: &mut StakerInfo,
+) -> Result<u64> {
+    let stream = reward_vault.index as usize;
+    let pending_rewards = calculate_pending_rewards(reward_vault, staker)?;
+
+    if pending_rewards > 0 {
+        staker.accumulated_rewards[stream] = staker.accumulated_rewards[stream]
+            .checked_add(pending_rewards)
+            .ok_or(StakingError::CalculationOverflow)?;
+    }
+    staker.reward_debts[stream] = calculate_reward_debt(reward_vault, staker.weighted_amount)?;
+
+    Ok(pending_rewards)
+}
+
+fn settle_all_pending_rewards(
+    streams: &[Account<RewardVault>],
+    staker: &mut StakerInfo,
+) -> Result<()> {
+    for reward_vault in streams {
+        settle_pending_rewards(reward_vault, staker)?;
+    }
+    Ok(())
+}
+
+// Deducts rewards from a stream's balance once they have been paid out or
+// restaked
+fn mark_rewards_paid(
+    staker: &mut StakerInfo,
+    stream: usize,
+    total_rewards: u64,
+    current_time: i64,
+) -> Result<()> {
+    staker.accumulated_rewards[stream] = staker.accumulated_rewards[stream]
+        .checked_sub(total_rewards)
+        .ok_or(StakingError::CalculationOverflow)?;
+    staker.last_reward_time = current_time;
+    staker.rewards_claimed[stream] = staker.rewards_claimed[stream]
+        .checked_add(total_rewards)
+        .ok_or(StakingError::CalculationOverflow)?;
+    Ok(())
+}
+
+fn calculate_reward_debt(reward_vault: &RewardVault, weighted_amount: u64) -> Result<u128> {
+    let debt = (weighted_amount as u128)
+        .checked_mul(reward_vault.accumulated_rewards_per_share)
+        .ok_or(StakingError::CalculationOverflow)?
+        .checked_div(REWARDS_PRECISION)
+        .ok_or(StakingError::CalculationOverflow)?;
+
+    Ok(debt)
+}
+
+// Recomputes the staker's share of the pool after its amount, tier or
+// positions changed. Pending rewards must already be settled on every stream.
+fn sync_stake_weight(
+    pool: &mut StakingPool,
+    streams: &[Account<RewardVault>],
+    staker: &mut StakerInfo,
+) -> Result<()> {
+    let weighted_amount = calculate_stake_weight(staker)?;
+
+    pool.total_weighted_stake = pool.total_weighted_stake
+        .checked_sub(staker.weighted_amount)
+        .ok_or(StakingError::CalculationOverflow)?
+        .checked_add(weighted_amount)
+        .ok_or(StakingError::CalculationOverflow)?;
+
+    staker.weighted_amount = weighted_amount;
+    for reward_vault in streams {
+        staker.reward_debts[reward_vault.index as usize] =
+            calculate_reward_debt(reward_vault, weighted_amount)?;
+    }
+
+    Ok(())
+}
+
+// The tier bonus applies to the staker's whole balance, while lock and early
+// adopter bonuses are tracked per position in `bonus_weight`
+fn calculate_stake_weight(staker: &StakerInfo) -> Result<u64> {
+    let multiplier = BASIS_POINTS // Base multiplier (100%)
+        .checked_add(get_tier_bonus(staker.tier))
+        .ok_or(StakingError::CalculationOverflow)?;
+
+    let base_weight = (staker.amount as u128)
+        .checked_mul(multiplier as u128)
+        .ok_or(StakingError::CalculationOverflow)?
+        .checked_div(BASIS_POINTS as u128)
+        .ok_or(StakingError::CalculationOverflow)?;
+
+    let weight = base_weight
+        .checked_add(staker.bonus_weight as u128)
+        .ok_or(StakingError::CalculationOverflow)?;
+
+    u64::try_from(weight).map_err(|_| StakingError::CalculationOverflow.into())
+}
+
+fn calculate_position_bonus(position: &StakePosition) -> Result<u64> {
+    // Add lock period bonus
+    let mut bonus = calculate_lock_bonus(position.lock_period)?;
+
+    // Add early adopter bonus
+    if position.is_early_adopter {
+        bonus = bonus
+            .checked_add(1000) // 10% bonus
+            .ok_or(StakingError::CalculationOverflow)?;
+    }
+
+    let bonus_weight = (position.amount as u128)
+        .checked_mul(bonus as u128)
+        .ok_or(StakingError::CalculationOverflow)?
+        .checked_div(BASIS_POINTS as u128)
+        .ok_or(StakingError::CalculationOverflow)?;
+
+    u64::try_from(bonus_weight).map_err(|_| StakingError::CalculationOverflow.into())
+}
+
+// Keeps the staker's aggregate bonus weight in step after a position's
+// amount or lock changed
+fn refresh_position_bonus(
+    staker: &mut StakerInfo,
+    position: &mut StakePosition,
+) -> Result<()> {
+    let bonus_weight = calculate_position_bonus(position)?;
+
+    staker.bonus_weight = staker.bonus_weight
+        .checked_sub(position.bonus_weight)
+        .ok_or(StakingError::CalculationOverflow)?
+        .checked_add(bonus_weight)
+        .ok_or(StakingError::CalculationOverflow)?;
+    position.bonus_weight = bonus_weight;
+
+    Ok(())
+}
+
+// Adds compounded rewards to a position as principal. Token transfers and
+// clearing the reward balance are done by the caller.
+fn credit_compound(
+    pool: &mut StakingPool,
+    streams: &[Account<RewardVault>],
+    staker: &mut StakerInfo,
+    position: &mut StakePosition,
+    compound_amount: u64,
+    current_time: i64,
+) -> Result<()> {
+    position.amount = position.amount
+        .checked_add(compound_amount)
+        .ok_or(StakingError::CalculationOverflow)?;
+    refresh_position_bonus(staker, position)?;
+
+    staker.amount = staker.amount
+        .checked_add(compound_amount)
+        .ok_or(StakingError::CalculationOverflow)?;
+    staker.tier = calculate_tier(staker.amount);
+    staker.last_compound_time = current_time;
+
+    sync_stake_weight(pool, streams, staker)?;
+    pool.total_staked = pool.total_staked
+        .checked_add(compound_amount)
+        .ok_or(StakingError::CalculationOverflow)?;
+
+    Ok(())
+}
+
+// Removes withdrawn principal from a position and drops the staker's
+// unclaimed rewards. Used by the emergency exits.
+fn debit_forfeited_stake(
+    pool: &mut StakingPool,
+    streams: &mut [Account<RewardVault>],
+    staker: &mut StakerInfo,
+    position: &mut StakePosition,
+    amount: u64,
+    current_time: i64,
+) -> Result<()> {
+    // Only the withdrawn amount's pro-rata share of unclaimed rewards is
+    // forfeited; it stays in the vault but is no longer owed
+    for reward_vault in streams.iter_mut() {
+        let stream = reward_vault.index as usize;
+        settle_pending_rewards(reward_vault, staker)?;
+
+        let forfeited = (staker.accumulated_rewards[stream] as u128)
+            .checked_mul(amount as u128)
+            .ok_or(StakingError::CalculationOverflow)?
+            .checked_div(staker.amount as u128)
+            .ok_or(StakingError::CalculationOverflow)?;
+        let forfeited = u64::try_from(forfeited)
+            .map_err(|_| StakingError::CalculationOverflow)?;
+
+        staker.accumulated_rewards[stream] = staker.accumulated_rewards[stream]
+            .checked_sub(forfeited)
+            .ok_or(StakingError::CalculationOverflow)?;
+        release_unclaimed_rewards(reward_vault, forfeited);
+    }
+
+    position.amount = position.amount
+        .checked_sub(amount)
+        .ok_or(StakingError::CalculationOverflow)?;
+    refresh_position_bonus(staker, position)?;
+
+    staker.amount = staker.amount
+        .checked_sub(amount)
+        .ok_or(StakingError::CalculationOverflow)?;
+    staker.tier = calculate_tier(staker.amount);
+    staker.last_reward_time = current_time;
+    release_active_staker(pool, staker)?;
+
+    sync_stake_weight(pool, streams, staker)?;
+    pool.total_staked = pool.total_staked
+        .checked_sub(amount)
+        .ok_or(StakingError::CalculationOverflow)?;
+
+    Ok(())
+}
+
+// Gross rewards leaving the reward vault, including the treasury's cut
+fn record_reward_payout(
+    pool: &mut StakingPool,
+    total_rewards: u64,
+    treasury_fee: u64,
+) -> Result<()> {
+    pool.total_rewards_distributed = pool.total_rewards_distributed
+        .checked_add(total_rewards)
+        .ok_or(StakingError::CalculationOverflow)?;
+    pool.total_treasury_fees = pool.total_treasury_fees
+        .checked_add(treasury_fee)
+        .ok_or(StakingError::CalculationOverflow)?;
+    Ok(())
+}
+
+fn transfer_from_stake_vault<'info>(
+    token_program: &Program<'info, Token>,
+    pool: &Account<'info, StakingPool>,
+    stake_token_account: &Account<'info, TokenAccount>,
+    to: AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    token::transfer(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            Transfer {
+                from: stake_token_account.to_account_info(),
+                to,
+                authority: pool.to_account_info(),
+            },
+            &[&[
+                b"staking_pool".as_ref(),
+                pool.stake_mint.as_ref(),
+                &pool.pool_id.to_le_bytes(),
+                &[pool.bump],
+            ]],
+        ),
+        amount,
+    )
+}
+
+fn transfer_from_reward_vault<'info>(
+    token_program: &Program<'info, Token>,
+    reward_vault: &Account<'info, RewardVault>,
+    reward_vault_token: &Account<'info, TokenAccount>,
+    to: AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+
+    token::transfer(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            Transfer {
+                from: reward_vault_token.to_account_info(),
+                to,
+                authority: reward_vault.to_account_info(),
+            },
+            &[&[
+                b"reward_vault".as_ref(),
+                reward_vault.pool.as_ref(),
+                &[reward_vault.index],
+                &[reward_vault.bump],
+            ]],
+        ),
+        amount,
+    )
+}
+
+// Instructions that change stake weight take the pool's reward streams, in
+// index order, as their leading remaining accounts. Each stream is brought up
+// to date as it is loaded.
+fn load_reward_streams<'info>(
+    pool: &Account<StakingPool>,
+    remaining_accounts: &[AccountInfo<'info>],
+    current_time: i64,
+) -> Result<Vec<Account<'info, RewardVault>>> {
+    let stream_count = pool.reward_stream_count as usize;
+    require!(
+        remaining_accounts.len() >= stream_count,
+        StakingError::InvalidRewardStream
+    );
+
+    let mut streams = Vec::with_capacity(stream_count);
+    for (index, info) in remaining_accounts[..stream_count].iter().enumerate() {
+        let mut reward_vault = load_reward_stream(pool.key(), info, index)?;
+        update_reward_vault(&mut reward_vault, pool, current_time)?;
+        streams.push(reward_vault);
+    }
+
+    Ok(streams)
+}
+
+fn load_reward_stream<'info>(
+    pool: Pubkey,
+    info: &AccountInfo<'info>,
+    index: usize,
+) -> Result<Account<'info, RewardVault>> {
+    require!(info.is_writable, StakingError::InvalidRewardStream);
+
+    let reward_vault = Account::<RewardVault>::try_from(info)?;
+    require_keys_eq!(reward_vault.pool, pool, StakingError::InvalidRewardStream);
+    require!(
+        reward_vault.index as usize == index,
+        StakingError::InvalidRewardStream
+    );
+
+    Ok(reward_vault)
+}
+
+// Token balances of the streams' vaults, passed in stream order after the
+// streams themselves
+fn load_reward_stream_balances(
+    streams: &[Account<RewardVault>],
+    remaining_accounts: &[AccountInfo],
+) -> Result<Vec<u64>> {
+    require!(
+        remaining_accounts.len() >= streams.len(),
+        StakingError::InvalidRewardStream
+    );
+
+    streams
+        .iter()
+        .zip(remaining_accounts)
+        .map(|(reward_vault, info)| {
+            require_keys_eq!(
+                info.key(),
+                reward_vault.token_account,
+                StakingError::InvalidTokenAccount
+            );
+            Ok(Account::<TokenAccount>::try_from(info)?.amount)
+        })
+        .collect()
+}
+
+// Anchor only writes back accounts declared in the context, so streams
+// loaded from remaining accounts are serialized here
+fn persist_reward_streams(streams: &[Account<RewardVault>]) -> Result<()> {
+    for reward_vault in streams {
+        reward_vault.exit(&crate::ID)?;
+    }
+    Ok(())
+}
+
+fn reward_stream_index(streams: &[Account<RewardVault>], token_account: Pubkey) -> Option<usize> {
+    streams
+        .iter()
+        .position(|reward_vault| reward_vault.token_account == token_account)
+}
+
+fn calculate_fee(amount: u64, fee_bps: u64) -> Result<u64> {
+    let fee = (amount as u128)
+        .checked_mul(fee_bps as u128)
+        .ok_or(StakingError::CalculationOverflow)?
+        .checked_div(BASIS_POINTS as u128)
+        .ok_or(StakingError::CalculationOverflow)?;
+
+    u64::try_from(fee).map_err(|_| StakingError::CalculationOverflow.into())
+}
+
+// Initializes a freshly created position and folds it into the staker's
+// totals. The caller syncs the stake weight afterwards.
+fn open_position(
+    pool: &mut StakingPool,
+    staker: &mut Account<StakerInfo>,
+    position: &mut StakePosition,
+    amount: u64,
+    lock_period: i64,
+    current_time: i64,
+    bump: u8,
+) -> Result<()> {
+    position.owner = staker.owner;
+    position.staker = staker.key();
+    position.index = staker.position_count;
+    position.amount = amount;
+    position.lock_period = lock_period;
+    position.start_time = current_time;
+    position.unlock_time = current_time
+        .checked_add(lock_period)
+        .ok_or(StakingError::CalculationOverflow)?;
+    position.is_early_adopter = current_time <= pool.early_adopter_deadline;
+    position.bump = bump;
+    refresh_position_bonus(staker, position)?;
+
+    if staker.amount == 0 {
+        staker.start_time = current_time;
+        pool.active_stakers = pool.active_stakers
+            .checked_add(1)
+            .ok_or(StakingError::CalculationOverflow)?;
+    }
+    staker.amount = staker.amount
+        .checked_add(amount)
+        .ok_or(StakingError::CalculationOverflow)?;
+    staker.last_reward_time = current_time;
+    staker.tier = calculate_tier(staker.amount);
+    staker.is_early_adopter = staker.is_early_adopter || position.is_early_adopter;
+    staker.position_count = staker.position_count
+        .checked_add(1)
+        .ok_or(StakingError::CalculationOverflow)?;
+    staker.open_positions = staker.open_positions
+        .checked_add(1)
+        .ok_or(StakingError::CalculationOverflow)?;
+
+    Ok(())
+}
+
+// A staker stops counting as active once an exit empties its stake
+fn release_active_staker(pool: &mut StakingPool, staker: &StakerInfo) -> Result<()> {
+    if staker.amount == 0 {
+        pool.active_stakers = pool.active_stakers
+            .checked_sub(1)
+            .ok_or(StakingError::CalculationOverflow)?;
+    }
+    Ok(())
+}
+
+fn close_position<'info>(
+    staker: &mut StakerInfo,
+    position: &Account<'info, StakePosition>,
+    receiver: AccountInfo<'info>,
+) -> Result<()> {
+    staker.open_positions = staker.open_positions
+        .checked_sub(1)
+        .ok_or(StakingError::CalculationOverflow)?;
+
+    position.close(receiver)
+}
+
+fn admin_role_slots(
+    pool: &mut StakingPool,
+    role: AdminRole,
+) -> (&mut Pubkey, &mut Option<Pubkey>) {
+    match role {
+        AdminRole::Authority => (&mut pool.authority, &mut pool.pending_authority),
+        AdminRole::EmergencyAdmin => (&mut pool.emergency_admin, &mut pool.pending_emergency_admin),
+        AdminRole::Treasury => (&mut pool.treasury, &mut pool.pending_treasury),
+    }
+}
+
+// Once an admin set is configured, authority-gated instructions expect the
+// admin set account as the first remaining account, followed by enough
+// co-signers to reach its threshold. The authority signer counts as well.
+fn require_admin_approval(
+    pool: &StakingPool,
+    authority: &AccountInfo<'_>,
+    remaining_accounts: &[AccountInfo<'_>],
+) -> Result<()> {
+    let admin_set_key = match pool.admin_set {
+        Some(key) => key,
+        None => return Ok(()),
+    };
+
+    let (admin_set_info, signers) = remaining_accounts
+        .split_first()
+        .ok_or(StakingError::InvalidAdminSetAccount)?;
+    require_keys_eq!(
+        admin_set_info.key(),
+        admin_set_key,
+        StakingError::InvalidAdminSetAccount
+    );
+    let admin_set = Account::<AdminSet>::try_from(admin_set_info)?;
+
+    verify_admin_signatures(&admin_set, authority, signers)
+}
+
+fn verify_admin_signatures(
+    admin_set: &AdminSet,
+    authority: &AccountInfo<'_>,
+    signers: &[AccountInfo<'_>],
+) -> Result<()> {
+    let mut approvals: Vec<Pubkey> = Vec::with_capacity(admin_set.signers.len());
+
+    // The authority and the extra signers carry unrelated lifetimes, so only
+    // the signer flag and key are taken from each
+    let candidates = std::iter::once((authority.is_signer, authority.key))
+        .chain(signers.iter().map(|info| (info.is_signer, info.key)));
+    for (is_signer, key) in candidates {
+        if is_signer && admin_set.signers.contains(key) && !approvals.contains(key) {
+            approvals.push(*key);
+        }
+    }
+
+    require!(
+        approvals.len() >= admin_set.threshold as usize,
+        StakingError::InsufficientAdminApprovals
+    );
+    Ok(())
+}
+
+fn validate_admin_set(signers: &[Pubkey], threshold: u8) -> Result<()> {
+    require!(
+        !signers.is_empty() && signers.len() <= MAX_ADMIN_SIGNERS,
+        StakingError::InvalidAdminSet
+    );
+    require!(
+        threshold > 0 && threshold as usize <= signers.len(),
+        StakingError::InvalidAdminSet
+    );
+
+    for (i, signer) in signers.iter().enumerate() {
+        require!(!signers[..i].contains(signer), StakingError::InvalidAdminSet);
+    }
+    Ok(())
+}
+
+fn validate_pool_config(config: &PoolConfig) -> Result<()> {
+    require!(config.early_adopter_period >= 0, StakingError::InvalidLockPeriod);
+    validate_lock_bounds(config.min_stake_duration, config.max_stake_duration)?;
+    validate_rewards_multiplier(config.rewards_multiplier)?;
+    validate_treasury_fee(config.treasury_fee)?;
+    validate_timelock_delay(config.admin_timelock_delay)?;
+    validate_unbonding_period(config.unbonding_period)?;
+    validate_early_unstake_penalty(config.early_unstake_penalty)?;
+    validate_compound_settings(config.keeper_fee, config.min_compound_interval)?;
+    validate_fee_split(config.treasury_fee, config.keeper_fee)?;
+    Ok(())
+}
+
+fn validate_lock_bounds(min_stake_duration: i64, max_stake_duration: i64) -> Result<()> {
+    require!(
+        min_stake_duration >= 0 && min_stake_duration <= max_stake_duration,
+        StakingError::InvalidLockPeriod
+    );
+    Ok(())
+}
+
+fn validate_rewards_multiplier(rewards_multiplier: u64) -> Result<()> {
+    require!(
+        rewards_multiplier > 0 && rewards_multiplier <= MAX_REWARDS_MULTIPLIER,
+        StakingError::InvalidRewardsMultiplier
+    );
+    Ok(())
+}
+
+fn validate_reward_rate(rewards_per_second: u64) -> Result<()> {
+    require!(
+        rewards_per_second <= MAX_REWARDS_PER_SECOND,
+        StakingError::InvalidRewardRate
+    );
+    Ok(())
+}
+
+fn validate_reward_window(reward_start_time: i64, reward_end_time: i64) -> Result<()> {
+    require!(reward_start_time >= 0, StakingError::InvalidRewardWindow);
+    require!(
+        reward_end_time == 0 || reward_end_time > reward_start_time,
+        StakingError::InvalidRewardWindow
+    );
+    Ok(())
+}
+
+fn validate_timelock_delay(delay: i64) -> Result<()> {
+    require!(
+        (0..=MAX_ADMIN_TIMELOCK_DELAY).contains(&delay),
+        StakingError::InvalidTimelockDelay
+    );
+    Ok(())
+}
+
+fn validate_admin_action(action: &AdminAction) -> Result<()> {
+    match *action {
+        AdminAction::SetTreasuryFee { treasury_fee } => validate_treasury_fee(treasury_fee),
+        AdminAction::SetRewardRate { stream, rewards_per_second } => {
+            require!(
+                (stream as usize) < MAX_REWARD_STREAMS,
+                StakingError::InvalidRewardStream
+            );
+            validate_reward_rate(rewards_per_second)
+        }
+        AdminAction::SetLockBounds { min_stake_duration, max_stake_duration } => {
+            validate_lock_bounds(min_stake_duration, max_stake_duration)
+        }
+        AdminAction::SetTimelockDelay { delay } => validate_timelock_delay(delay),
+    }
+}
+
+fn validate_unbonding_period(unbonding_period: i64) -> Result<()> {
+    require!(
+        (0..=MAX_UNBONDING_PERIOD).contains(&unbonding_period),
+        StakingError::InvalidUnbondingPeriod
+    );
+    Ok(())
+}
+
+fn validate_early_unstake_penalty(early_unstake_penalty: u64) -> Result<()> {
+    require!(
+        early_unstake_penalty <= MAX_EARLY_UNSTAKE_PENALTY,
+        StakingError::InvalidEarlyUnstakePenalty
+    );
+    Ok(())
+}
+
+fn validate_compound_settings(keeper_fee: u64, min_compound_interval: i64) -> Result<()> {
+    require!(keeper_fee <= MAX_KEEPER_FEE, StakingError::InvalidKeeperFee);
+    require!(min_compound_interval >= 0, StakingError::InvalidKeeperFee);
+    Ok(())
+}
+
+fn validate_treasury_fee(treasury_fee: u64) -> Result<()> {
+    require!(treasury_fee <= BASIS_POINTS, StakingError::InvalidTreasuryFee);
+    Ok(())
+}
+
+// The crank pays both fees out of the same compounded rewards
+fn validate_fee_split(treasury_fee: u64, keeper_fee: u64) -> Result<()> {
+    require!(
+        treasury_fee.saturating_add(keeper_fee) <= BASIS_POINTS,
+        StakingError::InvalidKeeperFee
+    );
+    Ok(())
+}
+
+fn calculate_early_unstake_penalty(
+    pool: &StakingPool,
+    position: &StakePosition,
+    amount: u64,
+    current_time: i64,
+) -> Result<u64> {
+    let remaining = position.unlock_time.saturating_sub(current_time).max(0) as u128;
+    let lock_period = position.lock_period.max(1) as u128;
+
+    // Scale the configured penalty by the share of the lock still remaining
+    let penalty_bps = (pool.early_unstake_penalty as u128)
+        .checked_mul(remaining.min(lock_period))
+        .ok_or(StakingError::CalculationOverflow)?
+        .checked_div(lock_period)
+        .ok_or(StakingError::CalculationOverflow)?;
+
+    let penalty = (amount as u128)
+        .checked_mul(penalty_bps)
+        .ok_or(StakingError::CalculationOverflow)?
+        .checked_div(BASIS_POINTS as u128)
+        .ok_or(StakingError::CalculationOverflow)?;
+
+    u64::try_from(penalty).map_err(|_| StakingError::CalculationOverflow.into())
+}
+
+// Moves `amount` of principal that has already left the staker's stake into
+// its unbonding balance
+fn queue_unbonding(
+    pool: &mut StakingPool,
+    staker: &mut StakerInfo,
+    amount: u64,
+    current_time: i64,
+) -> Result<()> {
+    staker.unbonding_release_time =
+        calculate_unbonding_release_time(pool, staker, amount, current_time)?;
+    staker.unbonding_amount = staker.unbonding_amount
+        .checked_add(amount)
+        .ok_or(StakingError::CalculationOverflow)?;
+    pool.total_unbonding = pool.total_unbonding
+        .checked_add(amount)
+        .ok_or(StakingError::CalculationOverflow)?;
+    Ok(())
+}
+
+// Release time for the unbonding balance once `amount` joins it. The balance
+// unbonds as one, so its release time is the amount-weighted average of the
+// existing release time and a full cooldown for the new amount; adding to the
+// queue never restarts the cooldown of stake already in it.
+fn calculate_unbonding_release_time(
+    pool: &StakingPool,
+    staker: &StakerInfo,
+    amount: u64,
+    current_time: i64,
+) -> Result<i64> {
+    let new_release_time = current_time
+        .checked_add(pool.unbonding_period)
+        .ok_or(StakingError::CalculationOverflow)?;
+    if staker.unbonding_amount == 0 {
+        return Ok(new_release_time);
+    }
+
+    let existing_release_time = staker.unbonding_release_time.max(current_time) as i128;
+    let total = (staker.unbonding_amount as i128)
+        .checked_add(amount as i128)
+        .ok_or(StakingError::CalculationOverflow)?;
+    let weighted = existing_release_time
+        .checked_mul(staker.unbonding_amount as i128)
+        .ok_or(StakingError::CalculationOverflow)?
+        .checked_add(
+            (new_release_time as i128)
+                .checked_mul(amount as i128)
+                .ok_or(StakingError::CalculationOverflow)?,
+        )
         .ok_or(StakingError::CalculationOverflow)?
-        .checked_div(10000)
+        .checked_div(total)
         .ok_or(StakingError::CalculationOverflow)?;
 
-    Ok(rewards as u64)
+    i64::try_from(weighted).map_err(|_| StakingError::CalculationOverflow.into())
 }
 
 fn calculate_tier(amount: u64) -> u8 {
@@ -6,7 +6,8 @@ pub mod context;
 pub mod events;
 pub mod errors;
 
-use instructions::*;
+use context::*;
+use state::{AdminAction, AdminRole, PauseReason, PenaltyDestination};
 
 declare_id!("your_program_id");
 
@@ -16,9 +17,136 @@ pub mod gent_staking {
 
     pub fn initialize_pool(
         ctx: Context<InitializePool>,
+        pool_id: u64,
         config: PoolConfig,
     ) -> Result<()> {
-        instructions::initialize_pool(ctx, config)
+        instructions::initialize_pool(ctx, pool_id, config)
+    }
+
+    pub fn initialize_reward_vault(
+        ctx: Context<InitializeRewardVault>,
+        rewards_per_second: u64,
+        reward_start_time: i64,
+        reward_end_time: i64,
+        funding: u64,
+    ) -> Result<()> {
+        instructions::initialize_reward_vault(
+            ctx,
+            rewards_per_second,
+            reward_start_time,
+            reward_end_time,
+            funding,
+        )
+    }
+
+    pub fn fund_reward_vault(
+        ctx: Context<FundRewardVault>,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::fund_reward_vault(ctx, amount)
+    }
+
+    pub fn update_pool_config(
+        ctx: Context<UpdatePoolConfig>,
+        update: PoolConfigUpdate,
+    ) -> Result<()> {
+        instructions::update_pool_config(ctx, update)
+    }
+
+    pub fn queue_admin_action(
+        ctx: Context<QueueAdminAction>,
+        action: AdminAction,
+    ) -> Result<()> {
+        instructions::queue_admin_action(ctx, action)
+    }
+
+    pub fn cancel_admin_action(ctx: Context<CancelAdminAction>) -> Result<()> {
+        instructions::cancel_admin_action(ctx)
+    }
+
+    pub fn execute_admin_action<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteAdminAction<'info>>,
+    ) -> Result<()> {
+        instructions::execute_admin_action(ctx)
+    }
+
+    pub fn initialize_admin_set(
+        ctx: Context<InitializeAdminSet>,
+        signers: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        instructions::initialize_admin_set(ctx, signers, threshold)
+    }
+
+    pub fn update_admin_set(
+        ctx: Context<UpdateAdminSet>,
+        signers: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        instructions::update_admin_set(ctx, signers, threshold)
+    }
+
+    pub fn propose_authority(
+        ctx: Context<ProposeAdminTransfer>,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        instructions::propose_admin_transfer(ctx, AdminRole::Authority, new_authority)
+    }
+
+    pub fn accept_authority(ctx: Context<AcceptAdminTransfer>) -> Result<()> {
+        instructions::accept_admin_transfer(ctx, AdminRole::Authority)
+    }
+
+    pub fn propose_emergency_admin(
+        ctx: Context<ProposeAdminTransfer>,
+        new_emergency_admin: Pubkey,
+    ) -> Result<()> {
+        instructions::propose_admin_transfer(ctx, AdminRole::EmergencyAdmin, new_emergency_admin)
+    }
+
+    pub fn accept_emergency_admin(ctx: Context<AcceptAdminTransfer>) -> Result<()> {
+        instructions::accept_admin_transfer(ctx, AdminRole::EmergencyAdmin)
+    }
+
+    pub fn propose_treasury(
+        ctx: Context<ProposeAdminTransfer>,
+        new_treasury: Pubkey,
+    ) -> Result<()> {
+        instructions::propose_admin_transfer(ctx, AdminRole::Treasury, new_treasury)
+    }
+
+    pub fn accept_treasury(ctx: Context<AcceptAdminTransfer>) -> Result<()> {
+        instructions::accept_admin_transfer(ctx, AdminRole::Treasury)
+    }
+
+    pub fn pause_pool(
+        ctx: Context<SetPoolPaused>,
+        reason: PauseReason,
+    ) -> Result<()> {
+        instructions::pause_pool(ctx, reason)
+    }
+
+    pub fn resume_pool(ctx: Context<SetPoolPaused>) -> Result<()> {
+        instructions::resume_pool(ctx)
+    }
+
+    pub fn set_reward_rate(
+        ctx: Context<SetRewardRate>,
+        rewards_per_second: u64,
+    ) -> Result<()> {
+        instructions::set_reward_rate(ctx, rewards_per_second)
+    }
+
+    pub fn notify_reward_amount(
+        ctx: Context<NotifyRewardAmount>,
+        amount: u64,
+        duration: i64,
+    ) -> Result<()> {
+        instructions::notify_reward_amount(ctx, amount, duration)
+    }
+
+    pub fn recover_leftover_rewards(ctx: Context<RecoverLeftoverRewards>) -> Result<()> {
+        instructions::recover_leftover_rewards(ctx)
     }
 
     pub fn create_stake_account(
@@ -28,6 +156,10 @@ pub mod gent_staking {
         instructions::create_stake_account(ctx, delegated_authority)
     }
 
+    pub fn close_stake_account(ctx: Context<CloseStakeAccount>) -> Result<()> {
+        instructions::close_stake_account(ctx)
+    }
+
     pub fn stake(
         ctx: Context<Stake>,
         amount: u64,
@@ -43,12 +175,87 @@ pub mod gent_staking {
         instructions::unstake(ctx, amount)
     }
 
+    pub fn early_unstake(
+        ctx: Context<EarlyUnstake>,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::early_unstake(ctx, amount)
+    }
+
+    pub fn request_unstake(
+        ctx: Context<RequestUnstake>,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::request_unstake(ctx, amount)
+    }
+
+    pub fn withdraw_unbonded(ctx: Context<WithdrawUnbonded>) -> Result<()> {
+        instructions::withdraw_unbonded(ctx)
+    }
+
+    pub fn cancel_unbonding(
+        ctx: Context<CancelUnbonding>,
+        lock_period: i64,
+    ) -> Result<()> {
+        instructions::cancel_unbonding(ctx, lock_period)
+    }
+
+    pub fn extend_lock(
+        ctx: Context<ExtendLock>,
+        new_lock_period: i64,
+    ) -> Result<()> {
+        instructions::extend_lock(ctx, new_lock_period)
+    }
+
     pub fn claim_rewards(ctx: Context<ClaimRewards>) -> Result<()> {
         instructions::claim_rewards(ctx)
     }
 
-    pub fn emergency_unstake(ctx: Context<EmergencyUnstake>) -> Result<()> {
-        instructions::emergency_unstake(ctx)
+    pub fn claim_all_rewards<'info>(
+        ctx: Context<'_, '_, '_, 'info, ClaimAllRewards<'info>>,
+    ) -> Result<()> {
+        instructions::claim_all_rewards(ctx)
+    }
+
+    pub fn compound_rewards<'info>(
+        ctx: Context<'_, '_, '_, 'info, CompoundRewards<'info>>,
+    ) -> Result<()> {
+        instructions::compound_rewards(ctx)
+    }
+
+    pub fn set_auto_compound(
+        ctx: Context<SetAutoCompound>,
+        enabled: bool,
+    ) -> Result<()> {
+        instructions::set_auto_compound(ctx, enabled)
+    }
+
+    pub fn crank_compound<'info>(
+        ctx: Context<'_, '_, '_, 'info, CrankCompound<'info>>,
+    ) -> Result<()> {
+        instructions::crank_compound(ctx)
+    }
+
+    pub fn emergency_unstake(
+        ctx: Context<EmergencyUnstake>,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::emergency_unstake(ctx, amount)
+    }
+
+    pub fn set_emergency_mode(
+        ctx: Context<SetEmergencyMode>,
+        enabled: bool,
+    ) -> Result<()> {
+        instructions::set_emergency_mode(ctx, enabled)
+    }
+
+    pub fn emergency_withdraw(ctx: Context<EmergencyWithdraw>) -> Result<()> {
+        instructions::emergency_withdraw(ctx)
+    }
+
+    pub fn get_pool_stats(ctx: Context<GetPoolStats>) -> Result<PoolStats> {
+        instructions::get_pool_stats(ctx)
     }
 }
 
@@ -59,4 +266,40 @@ pub struct PoolConfig {
     pub max_stake_duration: i64,
     pub rewards_multiplier: u64,
     pub treasury_fee: u64,
+    pub admin_timelock_delay: i64,
+    pub unbonding_period: i64,
+    pub early_unstake_penalty: u64,
+    pub penalty_destination: PenaltyDestination,
+    pub keeper_fee: u64,
+    pub min_compound_interval: i64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct PoolConfigUpdate {
+    pub min_stake_duration: Option<i64>,
+    pub max_stake_duration: Option<i64>,
+    pub rewards_multiplier: Option<u64>,
+    pub treasury_fee: Option<u64>,
+    pub unbonding_period: Option<i64>,
+    pub early_unstake_penalty: Option<u64>,
+    pub penalty_destination: Option<PenaltyDestination>,
+    pub keeper_fee: Option<u64>,
+    pub min_compound_interval: Option<i64>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct PoolStats {
+    pub total_staked: u64,
+    pub total_weighted_stake: u64,
+    pub total_unbonding: u64,
+    pub total_rewards_distributed: u64,
+    pub total_treasury_fees: u64,
+    pub total_penalties: u64,
+    pub active_stakers: u64,
+    pub total_deposits: u64,
+    pub total_withdrawals: u64,
+    pub reward_stream_count: u8,
+    pub rewards_per_second: u64,   // Of the reward stream passed in
+    pub reward_vault_balance: u64,
+    pub unclaimed_rewards: u64,    // Owed to stakers out of reward_vault_balance
 }
\ No newline at end of file
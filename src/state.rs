@@ -1,21 +1,44 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::TokenAccount;
 
 #[account]
 pub struct StakingPool {
     pub authority: Pubkey,
     pub treasury: Pubkey,
     pub emergency_admin: Pubkey,
+    pub stake_mint: Pubkey,
+    pub pool_id: u64,             // Distinguishes pools sharing a stake mint
+    pub stake_vault: Pubkey,      // Token account holding staked principal
+    pub pending_authority: Option<Pubkey>,
+    pub pending_emergency_admin: Option<Pubkey>,
+    pub pending_treasury: Option<Pubkey>,
+    pub admin_set: Option<Pubkey>,   // M-of-N signer set gating authority actions
     pub total_staked: u64,
-    pub total_rewards_distributed: u64,
+    pub total_weighted_stake: u64,  // Sum of multiplier-weighted stakes sharing emissions
+    pub total_unbonding: u64,       // Withdrawn from stake but still in cooldown
+    pub total_rewards_distributed: u64, // Gross rewards paid out of the reward vault
+    pub total_treasury_fees: u64,
+    pub total_penalties: u64,     // Early unstake penalties, whichever destination
     pub launch_timestamp: i64,
     pub early_adopter_deadline: i64,
     pub paused: bool,
+    pub pause_reason: u8,
+    pub paused_at: i64,
+    pub emergency_mode: bool,     // Lets stakers withdraw principal without the emergency admin
     pub min_stake_duration: i64,
     pub max_stake_duration: i64,
-    pub stake_count: u64,
-    pub rewards_multiplier: u64,  // Basis points (1 = 0.01%)
+    pub total_deposits: u64,      // Number of stake calls
+    pub total_withdrawals: u64,   // Number of payouts of principal back to stakers
+    pub active_stakers: u64,      // Stakers with a non-zero stake
+    pub rewards_multiplier: u64,  // Basis points (1 = 0.01%), scales emissions pool-wide
     pub treasury_fee: u64,        // Basis points
+    pub admin_timelock_delay: i64, // Seconds a queued admin action must wait
+    pub unbonding_period: i64,    // Unstake cooldown in seconds, 0 for instant unstaking
+    pub early_unstake_penalty: u64, // Basis points charged when exiting at the start of a lock
+    pub penalty_destination: PenaltyDestination,
+    pub keeper_fee: u64,          // Basis points of compounded rewards paid to crank callers
+    pub min_compound_interval: i64,
+    pub proposal_count: u64,
+    pub reward_stream_count: u8,  // Reward vaults created so far, indexed from 0
     pub bump: u8,
 }
 
@@ -23,32 +46,92 @@ pub struct StakingPool {
 #[derive(Default)]
 pub struct StakerInfo {
     pub owner: Pubkey,
+    pub pool: Pubkey,
     pub delegated_authority: Option<Pubkey>,
     pub amount: u64,
     pub start_time: i64,
     pub last_reward_time: i64,
     pub last_compound_time: i64,
-    pub rewards_claimed: u64,
+    pub rewards_claimed: [u64; MAX_REWARD_STREAMS],
     pub tier: u8,
     pub is_early_adopter: bool,
-    pub accumulated_rewards: u64,
-    pub lock_period: i64,
-    pub unlock_time: i64,
+    pub accumulated_rewards: [u64; MAX_REWARD_STREAMS], // Settled but unpaid, per reward stream
+    pub weighted_amount: u64,     // Stake amount after tier, lock and early adopter bonuses
+    pub reward_debts: [u128; MAX_REWARD_STREAMS], // weighted_amount * accumulated_rewards_per_share at last settlement
+    pub bonus_weight: u64,        // Sum of lock and early adopter bonus weight across positions
+    pub position_count: u64,      // Positions ever opened, used as the next position index
+    pub open_positions: u64,
+    pub unbonding_amount: u64,
+    pub unbonding_release_time: i64,
+    pub auto_compound: bool,
+    pub auto_compound_position: u64, // Position the compound crank restakes into
     pub boost_factor: u64,        // Additional multiplier for special events
     pub staking_points: u64,      // Loyalty points system
     pub bump: u8,
 }
 
+#[account]
+pub struct StakePosition {
+    pub owner: Pubkey,
+    pub staker: Pubkey,
+    pub index: u64,
+    pub amount: u64,
+    pub lock_period: i64,
+    pub start_time: i64,
+    pub unlock_time: i64,
+    pub is_early_adopter: bool,
+    pub bonus_weight: u64,
+    pub bump: u8,
+}
+
 #[account]
 pub struct RewardVault {
+    pub pool: Pubkey,
+    pub index: u8,                // Reward stream index within the pool
     pub authority: Pubkey,
+    pub reward_mint: Pubkey,
     pub token_account: Pubkey,
     pub rewards_per_second: u64,
+    pub reward_start_time: i64,
+    pub reward_end_time: i64,     // 0 for an open-ended stream
     pub last_update_time: i64,
     pub accumulated_rewards_per_share: u128,
+    pub unclaimed_rewards: u64,   // Emitted to stakers but not yet paid out
     pub bump: u8,
 }
 
+pub const MAX_REWARD_STREAMS: usize = 4;
+pub const MAX_ADMIN_SIGNERS: usize = 10;
+
+#[account]
+pub struct AdminSet {
+    pub pool: Pubkey,
+    pub signers: Vec<Pubkey>,
+    pub threshold: u8,
+    pub bump: u8,
+}
+
+#[account]
+pub struct AdminProposal {
+    pub pool: Pubkey,
+    pub proposer: Pubkey,
+    pub id: u64,
+    pub action: AdminAction,
+    pub queued_at: i64,
+    pub eta: i64,
+    pub executed: bool,
+    pub cancelled: bool,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum AdminAction {
+    SetTreasuryFee { treasury_fee: u64 },
+    SetRewardRate { stream: u8, rewards_per_second: u64 },
+    SetLockBounds { min_stake_duration: i64, max_stake_duration: i64 },
+    SetTimelockDelay { delay: i64 },
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
 pub enum StakingTier {
     Bronze,    // 5,000 GENT
@@ -56,4 +139,26 @@ pub enum StakingTier {
     Gold,      // 50,000 GENT
     Platinum,  // 100,000 GENT
     Diamond,   // 500,000 GENT
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum PauseReason {
+    Maintenance,
+    SecurityIncident,
+    RewardMigration,
+    ProgramUpgrade,
+    Other,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum AdminRole {
+    Authority,
+    EmergencyAdmin,
+    Treasury,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum PenaltyDestination {
+    Treasury,
+    Stakers,
 }
\ No newline at end of file
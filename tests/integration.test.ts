@@ -25,6 +25,7 @@ describe('Gent Staking Integration Tests', () => {
 
   const mint = Keypair.generate();
   const emergencyAdmin = Keypair.generate();
+  const poolId = new anchor.BN(0);
 
   before(async () => {
     // Setup initial state
@@ -38,6 +39,15 @@ describe('Gent Staking Integration Tests', () => {
 
     // Initialize token mint and accounts
     // ... (similar to staking.test.ts)
+
+    [stakingPool] = await PublicKey.findProgramAddress(
+      [Buffer.from('staking_pool'), mint.publicKey.toBuffer(), poolId.toArrayLike(Buffer, 'le', 8)],
+      program.programId
+    );
+    [rewardVault] = await PublicKey.findProgramAddress(
+      [Buffer.from('reward_vault'), stakingPool.toBuffer(), Buffer.from([0])],
+      program.programId
+    );
   });
 
   describe('Full staking lifecycle', () => {
@@ -47,25 +57,40 @@ describe('Gent Staking Integration Tests', () => {
         earlyAdopterPeriod: new anchor.BN(7 * 24 * 60 * 60),
         minStakeDuration: new anchor.BN(30 * 24 * 60 * 60),
         maxStakeDuration: new anchor.BN(365 * 24 * 60 * 60),
-        rewardsMultiplier: new anchor.BN(100),
+        rewardsMultiplier: new anchor.BN(10000),
         treasuryFee: new anchor.BN(500),
+        adminTimelockDelay: new anchor.BN(0),
+        unbondingPeriod: new anchor.BN(0),
+        earlyUnstakePenalty: new anchor.BN(2000),
+        penaltyDestination: { treasury: {} },
+        keeperFee: new anchor.BN(50),
+        minCompoundInterval: new anchor.BN(24 * 60 * 60),
       };
 
+      const [stakeVault] = await PublicKey.findProgramAddress(
+        [Buffer.from('stake_vault'), stakingPool.toBuffer()],
+        program.programId
+      );
+
       await program.methods
-        .initializePool(config)
+        .initializePool(poolId, config)
         .accounts({
           stakingPool,
+          stakeMint: mint.publicKey,
+          stakeVault,
           authority: provider.wallet.publicKey,
           treasury,
           emergencyAdmin: emergencyAdmin.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
         })
         .rpc();
 
       // Create stake accounts for all users
       for (const user of users) {
         const [stakerInfo, _] = await PublicKey.findProgramAddress(
-          [Buffer.from('staker'), user.keypair.publicKey.toBuffer()],
+          [Buffer.from('staker'), stakingPool.toBuffer(), user.keypair.publicKey.toBuffer()],
           program.programId
         );
         user.stakerInfo = stakerInfo;
@@ -73,6 +98,7 @@ describe('Gent Staking Integration Tests', () => {
         await program.methods
           .createStakeAccount(null)
           .accounts({
+            stakingPool,
             stakerInfo,
             owner: user.keypair.publicKey,
             systemProgram: SystemProgram.programId,
@@ -93,18 +119,24 @@ describe('Gent Staking Integration Tests', () => {
       for (let i = 0; i < users.length - 1; i++) {
         const user = users[i];
         const scenario = scenarios[i];
+        const [stakePosition] = await PublicKey.findProgramAddress(
+          [Buffer.from('position'), user.stakerInfo!.toBuffer(), new anchor.BN(0).toArrayLike(Buffer, 'le', 8)],
+          program.programId
+        );
 
         await program.methods
           .stake(scenario.amount, scenario.duration)
           .accounts({
             stakingPool,
             stakerInfo: user.stakerInfo!,
+            stakePosition,
             user: user.keypair.publicKey,
             userTokenAccount: user.tokenAccount!,
             stakeTokenAccount,
-            rewardVault,
             tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
           })
+          .remainingAccounts([{ pubkey: rewardVault, isWritable: true, isSigner: false }])
           .signers([user.keypair])
           .rpc();
       }
@@ -18,10 +18,23 @@ describe('Gent Staking Tests', () => {
   let userTokenAccount: PublicKey;
   let stakeTokenAccount: PublicKey;
   let rewardVault: PublicKey;
+  let rewardVaultToken: PublicKey;
+  let funderTokenAccount: PublicKey;
   let treasury: PublicKey;
+  let otherTokenAccount: PublicKey;
 
   const user = Keypair.generate();
+  const otherUser = Keypair.generate();
   const emergencyAdmin = Keypair.generate();
+  const poolId = new anchor.BN(0);
+  const DAY = 24 * 60 * 60;
+
+  // Decodes the events emitted by a confirmed transaction
+  const eventsOf = async (signature: string) => {
+    const tx = await provider.connection.getTransaction(signature, { commitment: 'confirmed' });
+    const parser = new anchor.EventParser(program.programId, program.coder);
+    return Array.from(parser.parseLogs(tx!.meta!.logMessages!));
+  };
 
   before(async () => {
     // Airdrop SOL to user
@@ -30,6 +43,11 @@ describe('Gent Staking Tests', () => {
       2 * anchor.web3.LAMPORTS_PER_SOL
     );
     await provider.connection.confirmTransaction(signature);
+    const otherSignature = await provider.connection.requestAirdrop(
+      otherUser.publicKey,
+      2 * anchor.web3.LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction(otherSignature);
 
     // Create mint and token accounts
     mintAuthority = Keypair.generate();
@@ -42,9 +60,27 @@ describe('Gent Staking Tests', () => {
       TOKEN_PROGRAM_ID
     );
 
+    // Find PDAs for staking pool, reward vault and their token accounts
+    [stakingPool, stakingPoolBump] = await PublicKey.findProgramAddress(
+      [Buffer.from('staking_pool'), mint.publicKey.toBuffer(), poolId.toArrayLike(Buffer, 'le', 8)],
+      program.programId
+    );
+    [rewardVault] = await PublicKey.findProgramAddress(
+      [Buffer.from('reward_vault'), stakingPool.toBuffer(), Buffer.from([0])],
+      program.programId
+    );
+    [stakeTokenAccount] = await PublicKey.findProgramAddress(
+      [Buffer.from('stake_vault'), stakingPool.toBuffer()],
+      program.programId
+    );
+    [rewardVaultToken] = await PublicKey.findProgramAddress(
+      [Buffer.from('reward_vault_token'), rewardVault.toBuffer()],
+      program.programId
+    );
+
     userTokenAccount = await mint.createAccount(user.publicKey);
-    stakeTokenAccount = await mint.createAccount(provider.wallet.publicKey);
-    rewardVault = await mint.createAccount(provider.wallet.publicKey);
+    otherTokenAccount = await mint.createAccount(otherUser.publicKey);
+    funderTokenAccount = await mint.createAccount(provider.wallet.publicKey);
     treasury = await mint.createAccount(provider.wallet.publicKey);
 
     // Mint initial tokens to user
@@ -55,10 +91,19 @@ describe('Gent Staking Tests', () => {
       1000000000000 // 1,000,000 tokens
     );
 
-    // Find PDA for staking pool
-    [stakingPool, stakingPoolBump] = await PublicKey.findProgramAddress(
-      [Buffer.from('staking_pool')],
-      program.programId
+    await mint.mintTo(
+      otherTokenAccount,
+      mintAuthority.publicKey,
+      [mintAuthority],
+      100000000000 // 100,000 tokens
+    );
+
+    // Mint reward budget to the pool authority
+    await mint.mintTo(
+      funderTokenAccount,
+      mintAuthority.publicKey,
+      [mintAuthority],
+      500000000000 // 500,000 tokens
     );
   });
 
@@ -67,36 +112,200 @@ describe('Gent Staking Tests', () => {
       earlyAdopterPeriod: new anchor.BN(7 * 24 * 60 * 60), // 7 days
       minStakeDuration: new anchor.BN(30 * 24 * 60 * 60),  // 30 days
       maxStakeDuration: new anchor.BN(365 * 24 * 60 * 60), // 1 year
-      rewardsMultiplier: new anchor.BN(100),               // 1%
+      rewardsMultiplier: new anchor.BN(10000),             // 1x
       treasuryFee: new anchor.BN(500),                     // 5%
+      adminTimelockDelay: new anchor.BN(0),                // No timelock
+      unbondingPeriod: new anchor.BN(0),                   // Instant unstake
+      earlyUnstakePenalty: new anchor.BN(2000),            // 20% at lock start
+      penaltyDestination: { treasury: {} },
+      keeperFee: new anchor.BN(50),                        // 0.5% crank bounty
+      minCompoundInterval: new anchor.BN(24 * 60 * 60),    // Daily auto-compound
     };
 
     await program.methods
-      .initializePool(config)
+      .initializePool(poolId, config)
       .accounts({
         stakingPool,
+        stakeMint: mint.publicKey,
+        stakeVault: stakeTokenAccount,
         authority: provider.wallet.publicKey,
-        treasury,
+        treasury: provider.wallet.publicKey,
         emergencyAdmin: emergencyAdmin.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
       })
       .rpc();
 
     const poolAccount = await program.account.stakingPool.fetch(stakingPool);
     assert.ok(poolAccount.authority.equals(provider.wallet.publicKey));
-    assert.ok(poolAccount.treasury.equals(treasury));
+    assert.ok(poolAccount.treasury.equals(provider.wallet.publicKey));
     assert.ok(poolAccount.emergencyAdmin.equals(emergencyAdmin.publicKey));
+    assert.ok(poolAccount.stakeMint.equals(mint.publicKey));
+    assert.equal(poolAccount.poolId.toNumber(), poolId.toNumber());
+  });
+
+  it('Initializes the reward vault', async () => {
+    const funding = new anchor.BN(100000000000); // 100,000 tokens
+    const initializeRewardVault = (rewardsPerSecond: anchor.BN) =>
+      program.methods
+        .initializeRewardVault(rewardsPerSecond, new anchor.BN(0), new anchor.BN(0), funding) // Open-ended stream
+        .accounts({
+          stakingPool,
+          rewardVault,
+          rewardMint: mint.publicKey,
+          rewardVaultToken,
+          funderTokenAccount,
+          authority: provider.wallet.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .rpc();
+
+    // The initial deposit covers seven days of emissions only up to ~165k per second
+    try {
+      await initializeRewardVault(new anchor.BN(200000));
+      assert.fail('Created a stream emitting past its funded runway');
+    } catch (err) {
+      assert.include(err.toString(), 'InsufficientRewardFunding');
+    }
+
+    const rewardsPerSecond = new anchor.BN(1000);
+    await initializeRewardVault(rewardsPerSecond);
+
+    const vaultAccount = await program.account.rewardVault.fetch(rewardVault);
+    assert.ok(vaultAccount.tokenAccount.equals(rewardVaultToken));
+    assert.equal(vaultAccount.rewardsPerSecond.toNumber(), rewardsPerSecond.toNumber());
+    assert.equal(vaultAccount.index, 0);
+
+    const vaultTokenAccount = await mint.getAccountInfo(rewardVaultToken);
+    assert.equal(vaultTokenAccount.amount.toNumber(), funding.toNumber());
+
+    const poolAccount = await program.account.stakingPool.fetch(stakingPool);
+    assert.equal(poolAccount.rewardStreamCount, 1);
+  });
+
+  it('Funds the reward vault', async () => {
+    const fundAmount = new anchor.BN(100000000000); // 100,000 tokens
+    const vaultBefore = (await mint.getAccountInfo(rewardVaultToken)).amount;
+
+    await program.methods
+      .fundRewardVault(fundAmount)
+      .accounts({
+        stakingPool,
+        rewardVault,
+        rewardVaultToken,
+        funderTokenAccount,
+        authority: provider.wallet.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+    const vaultTokenAccount = await mint.getAccountInfo(rewardVaultToken);
+    assert.equal(vaultTokenAccount.amount.sub(vaultBefore).toNumber(), fundAmount.toNumber());
+  });
+
+  it('Sets the reward rate within the funded runway', async () => {
+    const setRewardRate = (rewardsPerSecond: anchor.BN) =>
+      program.methods
+        .setRewardRate(rewardsPerSecond)
+        .accounts({
+          stakingPool,
+          rewardVault,
+          rewardVaultToken,
+          authority: provider.wallet.publicKey,
+        })
+        .rpc();
+
+    // 200,000 tokens cover seven days of emissions only up to ~330k per second
+    try {
+      await setRewardRate(new anchor.BN(400000));
+      assert.fail('Raised the reward rate past the funded runway');
+    } catch (err) {
+      assert.include(err.toString(), 'InsufficientRewardFunding');
+    }
+
+    const rewardsPerSecond = new anchor.BN(2000);
+    await setRewardRate(rewardsPerSecond);
+
+    const vaultAccount = await program.account.rewardVault.fetch(rewardVault);
+    assert.equal(vaultAccount.rewardsPerSecond.toNumber(), rewardsPerSecond.toNumber());
+  });
+
+  it('Starts a reward campaign', async () => {
+    const campaignAmount = new anchor.BN(100000000000); // 100,000 tokens
+    const duration = new anchor.BN(30 * 24 * 60 * 60); // 30 days
+
+    await program.methods
+      .notifyRewardAmount(campaignAmount, duration)
+      .accounts({
+        stakingPool,
+        rewardVault,
+        rewardVaultToken,
+        funderTokenAccount,
+        authority: provider.wallet.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+    const vaultAccount = await program.account.rewardVault.fetch(rewardVault);
+    assert.equal(
+      vaultAccount.rewardEndTime.toNumber() - vaultAccount.rewardStartTime.toNumber(),
+      duration.toNumber()
+    );
+    assert.equal(
+      vaultAccount.rewardsPerSecond.toNumber(),
+      campaignAmount.div(duration).toNumber()
+    );
+
+    // A token deposit cannot be used to cut the running rate
+    try {
+      await program.methods
+        .notifyRewardAmount(new anchor.BN(1), new anchor.BN(365 * DAY))
+        .accounts({
+          stakingPool,
+          rewardVault,
+          rewardVaultToken,
+          funderTokenAccount,
+          authority: provider.wallet.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+      assert.fail('Lowered the reward rate through a campaign');
+    } catch (err) {
+      assert.include(err.toString(), 'RewardRateDecrease');
+    }
+
+    // Nothing can be recovered while the campaign is running
+    try {
+      await program.methods
+        .recoverLeftoverRewards()
+        .accounts({
+          stakingPool,
+          rewardVault,
+          rewardVaultToken,
+          destinationTokenAccount: funderTokenAccount,
+          authority: provider.wallet.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+      assert.fail('Recovered rewards before the campaign ended');
+    } catch (err) {
+      assert.include(err.toString(), 'RewardPeriodActive');
+    }
   });
 
   it('Creates a stake account', async () => {
     const [stakerInfo, _] = await PublicKey.findProgramAddress(
-      [Buffer.from('staker'), user.publicKey.toBuffer()],
+      [Buffer.from('staker'), stakingPool.toBuffer(), user.publicKey.toBuffer()],
       program.programId
     );
 
     await program.methods
       .createStakeAccount(null)
       .accounts({
+        stakingPool,
         stakerInfo,
         owner: user.publicKey,
         systemProgram: SystemProgram.programId,
@@ -107,33 +316,542 @@ describe('Gent Staking Tests', () => {
     const stakeAccount = await program.account.stakerInfo.fetch(stakerInfo);
     assert.ok(stakeAccount.owner.equals(user.publicKey));
     assert.equal(stakeAccount.amount.toNumber(), 0);
+
+    // An empty stake account does not count as an active staker
+    const poolAccount = await program.account.stakingPool.fetch(stakingPool);
+    assert.equal(poolAccount.activeStakers.toNumber(), 0);
   });
 
   it('Stakes tokens', async () => {
     const [stakerInfo, _] = await PublicKey.findProgramAddress(
-      [Buffer.from('staker'), user.publicKey.toBuffer()],
+      [Buffer.from('staker'), stakingPool.toBuffer(), user.publicKey.toBuffer()],
       program.programId
     );
 
     const stakeAmount = new anchor.BN(100000000000); // 100,000 tokens
     const lockPeriod = new anchor.BN(90 * 24 * 60 * 60); // 90 days
+    const [stakePosition] = await PublicKey.findProgramAddress(
+      [Buffer.from('position'), stakerInfo.toBuffer(), new anchor.BN(0).toArrayLike(Buffer, 'le', 8)],
+      program.programId
+    );
 
     await program.methods
       .stake(stakeAmount, lockPeriod)
       .accounts({
         stakingPool,
         stakerInfo,
+        stakePosition,
         user: user.publicKey,
         userTokenAccount,
         stakeTokenAccount,
-        rewardVault,
         tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
       })
+      .remainingAccounts([{ pubkey: rewardVault, isWritable: true, isSigner: false }])
       .signers([user])
       .rpc();
 
     const stakeAccount = await program.account.stakerInfo.fetch(stakerInfo);
     assert.equal(stakeAccount.amount.toNumber(), stakeAmount.toNumber());
+    assert.equal(stakeAccount.openPositions.toNumber(), 1);
+
+    const positionAccount = await program.account.stakePosition.fetch(stakePosition);
+    assert.equal(positionAccount.amount.toNumber(), stakeAmount.toNumber());
+    assert.equal(positionAccount.lockPeriod.toNumber(), lockPeriod.toNumber());
+
+    const poolAccount = await program.account.stakingPool.fetch(stakingPool);
+    assert.equal(poolAccount.totalDeposits.toNumber(), 1);
+    assert.equal(poolAccount.totalWithdrawals.toNumber(), 0);
+    assert.equal(poolAccount.activeStakers.toNumber(), 1);
+  });
+
+  it('Pauses and resumes the pool', async () => {
+    await program.methods
+      .pausePool({ maintenance: {} })
+      .accounts({
+        stakingPool,
+        admin: emergencyAdmin.publicKey,
+      })
+      .signers([emergencyAdmin])
+      .rpc();
+
+    let poolAccount = await program.account.stakingPool.fetch(stakingPool);
+    assert.ok(poolAccount.paused);
+    assert.equal(poolAccount.pauseReason, 0);
+
+    await program.methods
+      .resumePool()
+      .accounts({
+        stakingPool,
+        admin: provider.wallet.publicKey,
+      })
+      .rpc();
+
+    poolAccount = await program.account.stakingPool.fetch(stakingPool);
+    assert.ok(!poolAccount.paused);
+  });
+
+  it('Shares emissions pro rata to stake weight', async () => {
+    const precision = new anchor.BN('1000000000000');
+    const [stakerInfo] = await PublicKey.findProgramAddress(
+      [Buffer.from('staker'), stakingPool.toBuffer(), user.publicKey.toBuffer()],
+      program.programId
+    );
+    const [otherStakerInfo] = await PublicKey.findProgramAddress(
+      [Buffer.from('staker'), stakingPool.toBuffer(), otherUser.publicKey.toBuffer()],
+      program.programId
+    );
+    const [otherStakePosition] = await PublicKey.findProgramAddress(
+      [Buffer.from('position'), otherStakerInfo.toBuffer(), new anchor.BN(0).toArrayLike(Buffer, 'le', 8)],
+      program.programId
+    );
+
+    await program.methods
+      .createStakeAccount(null)
+      .accounts({
+        stakingPool,
+        stakerInfo: otherStakerInfo,
+        owner: otherUser.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([otherUser])
+      .rpc();
+
+    // Same amount and lock as the first staker, so both carry the same weight
+    await program.methods
+      .stake(new anchor.BN(100000000000), new anchor.BN(90 * 24 * 60 * 60))
+      .accounts({
+        stakingPool,
+        stakerInfo: otherStakerInfo,
+        stakePosition: otherStakePosition,
+        user: otherUser.publicKey,
+        userTokenAccount: otherTokenAccount,
+        stakeTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts([{ pubkey: rewardVault, isWritable: true, isSigner: false }])
+      .signers([otherUser])
+      .rpc();
+
+    // A late staker's debt covers everything emitted before it joined
+    let vaultAccount = await program.account.rewardVault.fetch(rewardVault);
+    const joined = await program.account.stakerInfo.fetch(otherStakerInfo);
+    assert.ok(vaultAccount.accumulatedRewardsPerShare.gtn(0));
+    assert.equal(
+      joined.rewardDebts[0].toString(),
+      joined.weightedAmount.mul(vaultAccount.accumulatedRewardsPerShare).div(precision).toString()
+    );
+
+    // Stop emissions so the accumulator holds still while both stakers claim
+    await new Promise(resolve => setTimeout(resolve, 3000));
+    await program.methods
+      .setRewardRate(new anchor.BN(0))
+      .accounts({
+        stakingPool,
+        rewardVault,
+        rewardVaultToken,
+        authority: provider.wallet.publicKey,
+      })
+      .rpc();
+    vaultAccount = await program.account.rewardVault.fetch(rewardVault);
+
+    const pendingRewards = async (staker: PublicKey) => {
+      const stakeAccount = await program.account.stakerInfo.fetch(staker);
+      return stakeAccount.accumulatedRewards[0].add(
+        stakeAccount.weightedAmount
+          .mul(vaultAccount.accumulatedRewardsPerShare)
+          .div(precision)
+          .sub(stakeAccount.rewardDebts[0])
+      );
+    };
+    const userOwed = await pendingRewards(stakerInfo);
+    const otherOwed = await pendingRewards(otherStakerInfo);
+
+    // Equal weights earn equally while both are staked, so the first staker
+    // is ahead by what it earned alone
+    assert.ok(otherOwed.gtn(0));
+    assert.ok(userOwed.gt(otherOwed));
+
+    const vaultBefore = (await mint.getAccountInfo(rewardVaultToken)).amount;
+    const treasuryBefore = (await mint.getAccountInfo(treasury)).amount;
+
+    for (const [owner, ownerTokenAccount, staker, owed] of [
+      [user, userTokenAccount, stakerInfo, userOwed],
+      [otherUser, otherTokenAccount, otherStakerInfo, otherOwed],
+    ] as [Keypair, PublicKey, PublicKey, anchor.BN][]) {
+      const before = (await mint.getAccountInfo(ownerTokenAccount)).amount;
+
+      await program.methods
+        .claimRewards()
+        .accounts({
+          stakingPool,
+          stakerInfo: staker,
+          user: owner.publicKey,
+          userTokenAccount: ownerTokenAccount,
+          rewardVault,
+          rewardVaultToken,
+          treasuryAccount: treasury,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([owner])
+        .rpc();
+
+      // 5% of each claim goes to the treasury
+      const after = (await mint.getAccountInfo(ownerTokenAccount)).amount;
+      assert.equal(after.sub(before).toString(), owed.sub(owed.muln(500).divn(10000)).toString());
+    }
+
+    const vaultAfter = (await mint.getAccountInfo(rewardVaultToken)).amount;
+    const treasuryAfter = (await mint.getAccountInfo(treasury)).amount;
+    assert.equal(vaultBefore.sub(vaultAfter).toString(), userOwed.add(otherOwed).toString());
+    assert.equal(
+      treasuryAfter.sub(treasuryBefore).toString(),
+      userOwed.muln(500).divn(10000).add(otherOwed.muln(500).divn(10000)).toString()
+    );
+  });
+
+  it('Decays the early unstake penalty over the lock', async () => {
+    const [stakerInfo] = await PublicKey.findProgramAddress(
+      [Buffer.from('staker'), stakingPool.toBuffer(), user.publicKey.toBuffer()],
+      program.programId
+    );
+    const [stakePosition] = await PublicKey.findProgramAddress(
+      [Buffer.from('position'), stakerInfo.toBuffer(), new anchor.BN(0).toArrayLike(Buffer, 'le', 8)],
+      program.programId
+    );
+
+    const earlyUnstake = (amount: anchor.BN) =>
+      program.methods
+        .earlyUnstake(amount)
+        .accounts({
+          stakingPool,
+          stakerInfo,
+          stakePosition,
+          user: user.publicKey,
+          userTokenAccount,
+          stakeTokenAccount,
+          rewardVaultToken,
+          treasuryTokenAccount: treasury,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts([{ pubkey: rewardVault, isWritable: true, isSigner: false }])
+        .signers([user])
+        .rpc({ commitment: 'confirmed' });
+
+    // Position 0 is a few minutes into its 90 day lock
+    const position = await program.account.stakePosition.fetch(stakePosition);
+    const amount = new anchor.BN(5000000000); // 5,000 tokens
+    const userBefore = (await mint.getAccountInfo(userTokenAccount)).amount;
+    const treasuryBefore = (await mint.getAccountInfo(treasury)).amount;
+
+    const [exited] = await eventsOf(await earlyUnstake(amount));
+
+    // The 20% penalty scales with the share of the lock still remaining
+    const lockPeriod = position.lockPeriod.toNumber();
+    const remaining = position.unlockTime.toNumber() - exited.data.timestamp.toNumber();
+    const penaltyBps = Math.floor((2000 * Math.min(remaining, lockPeriod)) / lockPeriod);
+    const penalty = amount.muln(penaltyBps).divn(10000);
+
+    assert.isBelow(penaltyBps, 2000);
+    assert.isAbove(penaltyBps, 1000);
+    assert.equal(exited.data.penalty.toString(), penalty.toString());
+    assert.equal(exited.data.releaseTime.toNumber(), 0);
+
+    const userAfter = (await mint.getAccountInfo(userTokenAccount)).amount;
+    const treasuryAfter = (await mint.getAccountInfo(treasury)).amount;
+    assert.equal(userAfter.sub(userBefore).toString(), amount.sub(penalty).toString());
+    assert.equal(treasuryAfter.sub(treasuryBefore).toString(), penalty.toString());
+  });
+
+  it('Keeps the release time of stake already unbonding', async () => {
+    const [stakerInfo] = await PublicKey.findProgramAddress(
+      [Buffer.from('staker'), stakingPool.toBuffer(), user.publicKey.toBuffer()],
+      program.programId
+    );
+    const [stakePosition] = await PublicKey.findProgramAddress(
+      [Buffer.from('position'), stakerInfo.toBuffer(), new anchor.BN(1).toArrayLike(Buffer, 'le', 8)],
+      program.programId
+    );
+
+    // Turn on a one day cooldown and allow unlocked positions
+    await program.methods
+      .updatePoolConfig({
+        minStakeDuration: new anchor.BN(0),
+        maxStakeDuration: null,
+        rewardsMultiplier: null,
+        treasuryFee: null,
+        unbondingPeriod: new anchor.BN(DAY),
+        earlyUnstakePenalty: null,
+        penaltyDestination: null,
+        keeperFee: null,
+        minCompoundInterval: null,
+      })
+      .accounts({
+        stakingPool,
+        authority: provider.wallet.publicKey,
+      })
+      .remainingAccounts([
+        { pubkey: rewardVault, isWritable: true, isSigner: false },
+        { pubkey: rewardVaultToken, isWritable: false, isSigner: false },
+      ])
+      .rpc();
+
+    await program.methods
+      .stake(new anchor.BN(10000000000), new anchor.BN(0)) // 10,000 tokens, no lock
+      .accounts({
+        stakingPool,
+        stakerInfo,
+        stakePosition,
+        user: user.publicKey,
+        userTokenAccount,
+        stakeTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts([{ pubkey: rewardVault, isWritable: true, isSigner: false }])
+      .signers([user])
+      .rpc();
+
+    const requestUnstake = (amount: anchor.BN) =>
+      program.methods
+        .requestUnstake(amount)
+        .accounts({
+          stakingPool,
+          stakerInfo,
+          stakePosition,
+          user: user.publicKey,
+        })
+        .remainingAccounts([{ pubkey: rewardVault, isWritable: true, isSigner: false }])
+        .signers([user])
+        .rpc({ commitment: 'confirmed' });
+
+    const firstAmount = new anchor.BN(6000000000); // 6,000 tokens
+    const secondAmount = new anchor.BN(1000000000); // 1,000 tokens
+
+    await requestUnstake(firstAmount);
+    const first = await program.account.stakerInfo.fetch(stakerInfo);
+
+    // Let the first request age before adding a small one on top
+    await new Promise(resolve => setTimeout(resolve, 4000));
+    const [requested] = await eventsOf(await requestUnstake(secondAmount));
+    const requestTime = requested.data.timestamp.toNumber();
+    const second = await program.account.stakerInfo.fetch(stakerInfo);
+
+    // The balance unbonds at the amount-weighted average of both release times
+    const expected = firstAmount
+      .mul(new anchor.BN(Math.max(first.unbondingReleaseTime.toNumber(), requestTime)))
+      .add(secondAmount.mul(new anchor.BN(requestTime + DAY)))
+      .div(firstAmount.add(secondAmount))
+      .toNumber();
+    assert.equal(second.unbondingAmount.toString(), firstAmount.add(secondAmount).toString());
+    assert.equal(second.unbondingReleaseTime.toNumber(), expected);
+    assert.isBelow(second.unbondingReleaseTime.toNumber(), requestTime + DAY);
+  });
+
+  it('Queues early exits behind the unbonding cooldown', async () => {
+    const [stakerInfo] = await PublicKey.findProgramAddress(
+      [Buffer.from('staker'), stakingPool.toBuffer(), user.publicKey.toBuffer()],
+      program.programId
+    );
+    const [stakePosition] = await PublicKey.findProgramAddress(
+      [Buffer.from('position'), stakerInfo.toBuffer(), new anchor.BN(0).toArrayLike(Buffer, 'le', 8)],
+      program.programId
+    );
+
+    const earlyUnstake = (amount: anchor.BN) =>
+      program.methods
+        .earlyUnstake(amount)
+        .accounts({
+          stakingPool,
+          stakerInfo,
+          stakePosition,
+          user: user.publicKey,
+          userTokenAccount,
+          stakeTokenAccount,
+          rewardVaultToken,
+          treasuryTokenAccount: treasury,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts([{ pubkey: rewardVault, isWritable: true, isSigner: false }])
+        .signers([user])
+        .rpc({ commitment: 'confirmed' });
+
+    const amount = new anchor.BN(1000000000); // 1,000 tokens
+    const before = await program.account.stakerInfo.fetch(stakerInfo);
+    const userBefore = (await mint.getAccountInfo(userTokenAccount)).amount;
+    const treasuryBefore = (await mint.getAccountInfo(treasury)).amount;
+
+    const [exited] = await eventsOf(await earlyUnstake(amount));
+    const penalty = exited.data.penalty;
+    const exitTime = exited.data.timestamp.toNumber();
+
+    // Only the penalty leaves the stake vault; the rest waits out the cooldown
+    const userAfter = (await mint.getAccountInfo(userTokenAccount)).amount;
+    const treasuryAfter = (await mint.getAccountInfo(treasury)).amount;
+    assert.ok(penalty.gtn(0));
+    assert.equal(userAfter.toString(), userBefore.toString());
+    assert.equal(treasuryAfter.sub(treasuryBefore).toString(), penalty.toString());
+
+    // It joins the balance already unbonding at the amount-weighted release time
+    const queued = amount.sub(penalty);
+    const expected = before.unbondingAmount
+      .mul(new anchor.BN(Math.max(before.unbondingReleaseTime.toNumber(), exitTime)))
+      .add(queued.mul(new anchor.BN(exitTime + DAY)))
+      .div(before.unbondingAmount.add(queued))
+      .toNumber();
+    const stakeAccount = await program.account.stakerInfo.fetch(stakerInfo);
+    assert.equal(
+      stakeAccount.unbondingAmount.toString(),
+      before.unbondingAmount.add(queued).toString()
+    );
+    assert.equal(stakeAccount.unbondingReleaseTime.toNumber(), expected);
+    assert.equal(exited.data.releaseTime.toNumber(), expected);
+  });
+
+  it('Keeps multiplier increases within the funded runway', async () => {
+    const updateRewardsMultiplier = (rewardsMultiplier: anchor.BN) =>
+      program.methods
+        .updatePoolConfig({
+          minStakeDuration: null,
+          maxStakeDuration: null,
+          rewardsMultiplier,
+          treasuryFee: null,
+          unbondingPeriod: null,
+          earlyUnstakePenalty: null,
+          penaltyDestination: null,
+          keeperFee: null,
+          minCompoundInterval: null,
+        })
+        .accounts({
+          stakingPool,
+          authority: provider.wallet.publicKey,
+        })
+        .remainingAccounts([
+          { pubkey: rewardVault, isWritable: true, isSigner: false },
+          { pubkey: rewardVaultToken, isWritable: false, isSigner: false },
+        ])
+        .rpc();
+
+    // ~104k tokens over what is left of the 30 day campaign
+    await program.methods
+      .setRewardRate(new anchor.BN(40000))
+      .accounts({
+        stakingPool,
+        rewardVault,
+        rewardVaultToken,
+        authority: provider.wallet.publicKey,
+      })
+      .rpc();
+
+    // The vault holds under 300,000 tokens, which covers 2x but not 5x
+    try {
+      await updateRewardsMultiplier(new anchor.BN(50000));
+      assert.fail('Raised the multiplier past the funded runway');
+    } catch (err) {
+      assert.include(err.toString(), 'InsufficientRewardFunding');
+    }
+
+    await updateRewardsMultiplier(new anchor.BN(20000));
+
+    const poolAccount = await program.account.stakingPool.fetch(stakingPool);
+    assert.equal(poolAccount.rewardsMultiplier.toNumber(), 20000);
+  });
+
+  it('Pays funded claims in full and releases what they owed', async () => {
+    const [stakerInfo] = await PublicKey.findProgramAddress(
+      [Buffer.from('staker'), stakingPool.toBuffer(), user.publicKey.toBuffer()],
+      program.programId
+    );
+
+    // Let the stream accrue at its new rate
+    await new Promise(resolve => setTimeout(resolve, 2000));
+
+    const signature = await program.methods
+      .claimRewards()
+      .accounts({
+        stakingPool,
+        stakerInfo,
+        user: user.publicKey,
+        userTokenAccount,
+        rewardVault,
+        rewardVaultToken,
+        treasuryAccount: treasury,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([user])
+      .rpc({ commitment: 'confirmed' });
+
+    const events = await eventsOf(signature);
+    assert.ok(events.some(event => event.name === 'RewardsClaimedEvent'));
+    assert.notOk(events.some(event => event.name === 'RewardVaultDepletedEvent'));
+
+    // Nothing is left owed to the staker, and the vault still covers what it
+    // owes everyone else
+    const stakeAccount = await program.account.stakerInfo.fetch(stakerInfo);
+    const vaultAccount = await program.account.rewardVault.fetch(rewardVault);
+    const vaultTokenAccount = await mint.getAccountInfo(rewardVaultToken);
+    assert.equal(stakeAccount.accumulatedRewards[0].toNumber(), 0);
+    assert.ok(vaultAccount.unclaimedRewards.lte(vaultTokenAccount.amount));
+  });
+
+  it('Requires M distinct admin set signers', async () => {
+    const admins = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+    const [adminSet] = await PublicKey.findProgramAddress(
+      [Buffer.from('admin_set'), stakingPool.toBuffer()],
+      program.programId
+    );
+
+    // 2-of-3 set that the pool authority is not part of. Every authority
+    // instruction on the pool needs its approvals from here on.
+    await program.methods
+      .initializeAdminSet(admins.map(admin => admin.publicKey), 2)
+      .accounts({
+        stakingPool,
+        adminSet,
+        authority: provider.wallet.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const fundAmount = new anchor.BN(1000000000); // 1,000 tokens
+    const fundWith = (approvers: Keypair[]) =>
+      program.methods
+        .fundRewardVault(fundAmount)
+        .accounts({
+          stakingPool,
+          rewardVault,
+          rewardVaultToken,
+          funderTokenAccount,
+          authority: provider.wallet.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts([
+          { pubkey: adminSet, isWritable: false, isSigner: false },
+          ...approvers.map(approver => ({ pubkey: approver.publicKey, isWritable: false, isSigner: true })),
+        ])
+        .signers(Array.from(new Set(approvers)))
+        .rpc();
+
+    const vaultBefore = (await mint.getAccountInfo(rewardVaultToken)).amount;
+
+    // One approval, and the same signer passed twice, both fall short
+    for (const approvers of [[admins[0]], [admins[0], admins[0]]]) {
+      try {
+        await fundWith(approvers);
+        assert.fail('Funded the vault below the admin threshold');
+      } catch (err) {
+        assert.include(err.toString(), 'InsufficientAdminApprovals');
+      }
+    }
+
+    let vaultTokenAccount = await mint.getAccountInfo(rewardVaultToken);
+    assert.equal(vaultTokenAccount.amount.toString(), vaultBefore.toString());
+
+    await fundWith([admins[0], admins[2]]);
+
+    vaultTokenAccount = await mint.getAccountInfo(rewardVaultToken);
+    assert.equal(vaultTokenAccount.amount.sub(vaultBefore).toString(), fundAmount.toString());
   });
 
   // Additional tests for unstaking, claiming rewards, etc.